@@ -1,21 +1,30 @@
-use std::{cell::RefCell, marker::PhantomData, mem, rc::Rc};
+use std::{cell::RefCell, marker::PhantomData, ops::Deref, rc::Rc, sync::mpsc};
 
 use godot::{
     classes::Node,
+    global::godot_error,
     obj::{Gd, Inherits},
 };
 use slotmap::Key;
 
 use crate::{
     ctx::Context,
-    system::{APP_NOTIFICATIONS, APPS, AppData, AppId},
-    view::{AnchorType, View, any::AnyView},
+    executor::{cancel_all, poll_tasks},
+    system::{
+        APP_CHANNELS, APP_NOTIFICATIONS, APPS, AppChannel, AppData, AppId, STATES, building,
+        caller, describe_created, free_state, run_subscribers, with_current_app,
+    },
+    view::computed::refresh,
+    view::store::{Store, get_or_create_store},
+    view::{AnchorType, View, any::AnyView, with_anchor},
 };
 
 pub struct App {
     id: AppId,
 }
 
+const DEFAULT_MAX_ITERATIONS: usize = 100;
+
 pub fn start<N, V, F>(root: Gd<N>, f: F) -> App
 where
     N: Inherits<Node>,
@@ -28,50 +37,262 @@ where
 
     let id = APPS.with_borrow_mut(|apps| {
         apps.insert_with_key(|id| {
+            let (sender, receiver) = mpsc::channel();
+            APP_CHANNELS.with_borrow_mut(|map| {
+                map.insert(
+                    id,
+                    AppChannel {
+                        sender,
+                        receiver,
+                        woken: Default::default(),
+                    },
+                )
+            });
+
             let mut ctx = Context {
                 app_id: id,
                 id_counter: 0,
                 path: vec![],
             };
 
-            let view_state = view.build(&mut ctx, &mut root, AnchorType::ChildOf);
+            let view_state = with_current_app(id, || {
+                building(true, || {
+                    view.build(&mut ctx, &mut root, AnchorType::ChildOf)
+                })
+            });
             let ctx = Rc::new(RefCell::new(ctx));
             AppData {
                 ctx: ctx.clone(),
                 view: Rc::new(RefCell::new(view)),
                 view_state: Rc::new(RefCell::new(view_state)),
                 root,
+                max_iterations: DEFAULT_MAX_ITERATIONS,
+                running: false,
+                stop_requested: false,
             }
         })
     });
     App { id }
 }
 impl App {
-    pub fn run(&self) {
-        let Some(paths) =
-            APP_NOTIFICATIONS.with_borrow_mut(|map| map.get_mut(self.id).map(mem::take))
+    /// Another handle to the same app.
+    pub(crate) fn handle(&self) -> App {
+        App { id: self.id }
+    }
+    pub(crate) fn root(&self) -> Gd<Node> {
+        APPS.with_borrow(|apps| apps[self.id].root.clone())
+    }
+    pub fn is_running(&self) -> bool {
+        APPS.with_borrow(|apps| apps.contains_key(self.id))
+    }
+    /// Tears down the view tree and frees every state of this app. Does nothing if already stopped.
+    ///
+    /// Called while the app is running, e.g. from a signal fired by a rebuild, the app
+    /// stops once `run` returns.
+    pub fn stop(&self) {
+        let deferred = APPS.with_borrow_mut(|apps| {
+            apps.get_mut(self.id).is_some_and(|app| {
+                app.stop_requested |= app.running;
+                app.running
+            })
+        });
+        if deferred {
+            return;
+        }
+
+        let Some(AppData {
+            ctx,
+            view,
+            view_state,
+            root,
+            ..
+        }) = APPS.with_borrow_mut(|apps| apps.remove(self.id))
         else {
             return;
         };
 
-        let (ctx, view, view_state, mut root) = APPS.with_borrow(|apps| {
-            let v = &apps[self.id];
-            (
-                v.ctx.clone(),
-                v.view.clone(),
-                v.view_state.clone(),
-                v.root.clone(),
-            )
+        // if the root was freed, the view nodes went with it, but the views
+        // still get torn down for everything else they clean up
+        let mut view_state = view_state.borrow_mut();
+        with_anchor(&root, |root| {
+            with_current_app(self.id, || {
+                view.borrow().teardown(
+                    &mut view_state,
+                    &mut ctx.borrow_mut(),
+                    root,
+                    AnchorType::ChildOf,
+                )
+            });
+            AnchorType::ChildOf.remove_and_free(root, view_state.anchor().clone());
+        });
+        drop(view_state);
+
+        let ids = STATES.with_borrow(|states| {
+            states
+                .iter()
+                .filter(|(_, data)| data.app_id == self.id)
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>()
         });
+        let states = ids.into_iter().filter_map(free_state).collect::<Vec<_>>();
+        drop(states);
+        cancel_all(self.id);
+        APP_NOTIFICATIONS.with_borrow_mut(|map| map.remove(self.id));
+        APP_CHANNELS.with_borrow_mut(|map| map.remove(self.id));
+    }
+    /// Wraps the app in a guard that stops it when dropped.
+    pub fn into_guard(self) -> AppGuard {
+        AppGuard { app: self }
+    }
+    /// Caps how many rounds of notifications a single `run` processes before giving up
+    /// on states that keep notifying each other.
+    pub fn set_max_iterations(&self, max_iterations: usize) {
+        APPS.with_borrow_mut(|apps| {
+            if let Some(app) = apps.get_mut(self.id) {
+                app.max_iterations = max_iterations;
+            }
+        });
+    }
+    /// Gets the store of type `T`, creating it with `init` if there is none yet.
+    #[track_caller]
+    pub fn store<T: 'static>(&self, init: impl FnOnce() -> T) -> Store<T> {
+        get_or_create_store(self.id, init, caller())
+    }
+    pub fn run(&self) {
+        with_current_app(self.id, || self.run_inner());
+    }
+    fn stop_requested(&self) -> bool {
+        APPS.with_borrow(|apps| apps.get(self.id).is_some_and(|app| app.stop_requested))
+    }
+    fn run_inner(&self) {
+        let Some((ctx, view, view_state, mut root, max_iterations)) = APPS.with_borrow(|apps| {
+            apps.get(self.id).map(|v| {
+                (
+                    v.ctx.clone(),
+                    v.view.clone(),
+                    v.view_state.clone(),
+                    v.root.clone(),
+                    v.max_iterations,
+                )
+            })
+        }) else {
+            return;
+        };
+        let was_running =
+            APPS.with_borrow_mut(|apps| std::mem::replace(&mut apps[self.id].running, true));
+        let _running = Running {
+            app: self,
+            was_running,
+        };
 
-        for path in paths {
-            view.borrow().notify_state(
-                &path,
-                &mut view_state.borrow_mut(),
-                &mut ctx.borrow_mut(),
-                &mut root,
-                AnchorType::ChildOf,
-            );
+        let messages = APP_CHANNELS.with_borrow(|map| {
+            map.get(self.id)
+                .map(|c| c.receiver.try_iter().collect::<Vec<_>>())
+                .unwrap_or_default()
+        });
+        for message in messages {
+            message();
+        }
+        poll_tasks(self.id);
+
+        // rebuilds can notify again, keep going until nothing is left
+        let mut iterations = 0;
+        loop {
+            let settled = APP_NOTIFICATIONS
+                .with_borrow(|map| map.get(self.id).is_none_or(|n| n.is_settled()));
+            if settled || self.stop_requested() {
+                break;
+            }
+            if iterations == max_iterations {
+                let states = APP_NOTIFICATIONS.with_borrow(|map| {
+                    STATES.with_borrow(|data| {
+                        map[self.id]
+                            .states()
+                            .map(|(path, id)| {
+                                let created = data.get(id).map(|d| describe_created(d.created));
+                                format!("\n    {id:?} at {path:?}{}", created.unwrap_or_default())
+                            })
+                            .collect::<String>()
+                    })
+                });
+                godot_error!(
+                    "notifications did not settle after {max_iterations} iterations, these states kept re-notifying:{states}"
+                );
+                break;
+            }
+            iterations += 1;
+
+            let (dirty, changed) = APP_NOTIFICATIONS.with_borrow_mut(|map| {
+                map.get_mut(self.id)
+                    .map(|n| (n.take_dirty(), n.take_changed()))
+                    .unwrap_or_default()
+            });
+            for id in dirty {
+                refresh(id);
+            }
+            for id in changed {
+                run_subscribers(id);
+            }
+
+            let paths = APP_NOTIFICATIONS
+                .with_borrow(|map| map.get(self.id).map(|n| n.sorted()))
+                .unwrap_or_default();
+            for path in paths {
+                // already covered by the rebuild of an ancestor
+                let pending = APP_NOTIFICATIONS
+                    .with_borrow_mut(|map| map.get_mut(self.id).is_some_and(|n| n.remove(&path)));
+                if !pending {
+                    continue;
+                }
+                if self.stop_requested() {
+                    break;
+                }
+                building(true, || {
+                    view.borrow().notify_state(
+                        &path,
+                        &mut view_state.borrow_mut(),
+                        &mut ctx.borrow_mut(),
+                        &mut root,
+                        AnchorType::ChildOf,
+                    )
+                });
+            }
         }
     }
 }
+
+/// Clears `AppData::running` once `run` returns, and does a stop requested meanwhile.
+struct Running<'a> {
+    app: &'a App,
+    was_running: bool,
+}
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        let stop = APPS.with_borrow_mut(|apps| {
+            apps.get_mut(self.app.id).is_some_and(|app| {
+                app.running = self.was_running;
+                !app.running && app.stop_requested
+            })
+        });
+        // while unwinding the views might be half rebuilt, a later `stop` still works
+        if stop && !std::thread::panicking() {
+            self.app.stop();
+        }
+    }
+}
+
+pub struct AppGuard {
+    app: App,
+}
+impl Deref for AppGuard {
+    type Target = App;
+
+    fn deref(&self) -> &Self::Target {
+        &self.app
+    }
+}
+impl Drop for AppGuard {
+    fn drop(&mut self) {
+        self.app.stop();
+    }
+}
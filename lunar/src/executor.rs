@@ -0,0 +1,91 @@
+use std::{
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+};
+
+use ahash::AHashSet;
+use parking_lot::Mutex;
+
+use crate::system::{APP_CHANNELS, AppId, TASKS, TaskData, TaskId};
+
+struct TaskWaker {
+    id: TaskId,
+    woken: Arc<Mutex<Vec<TaskId>>>,
+}
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.lock().push(self.id);
+    }
+}
+
+fn woken(app_id: AppId) -> Option<Arc<Mutex<Vec<TaskId>>>> {
+    APP_CHANNELS.with_borrow(|map| map.get(app_id).map(|c| c.woken.clone()))
+}
+
+pub(crate) fn spawn<F>(app_id: AppId, future: F) -> TaskId
+where
+    F: Future<Output = ()> + 'static,
+{
+    let id = TASKS.with_borrow_mut(|tasks| {
+        tasks.insert(TaskData {
+            app_id,
+            future: Some(Box::pin(future)),
+        })
+    });
+    if let Some(woken) = woken(app_id) {
+        woken.lock().push(id);
+    }
+    id
+}
+
+pub(crate) fn cancel(id: TaskId) {
+    let task = TASKS.with_borrow_mut(|tasks| tasks.remove(id));
+    drop(task);
+}
+
+pub(crate) fn cancel_all(app_id: AppId) {
+    let tasks = TASKS.with_borrow_mut(|tasks| {
+        let ids = tasks
+            .iter()
+            .filter(|(_, task)| task.app_id == app_id)
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        ids.into_iter()
+            .filter_map(|id| tasks.remove(id))
+            .collect::<Vec<_>>()
+    });
+    drop(tasks);
+}
+
+/// Polls every task of the app that was woken since the last call.
+pub(crate) fn poll_tasks(app_id: AppId) {
+    let Some(woken) = woken(app_id) else {
+        return;
+    };
+    let ids = woken.lock().drain(..).collect::<AHashSet<_>>();
+
+    for id in ids {
+        // the future is taken out so it can spawn or cancel tasks while being polled
+        let Some(mut future) =
+            TASKS.with_borrow_mut(|tasks| tasks.get_mut(id).and_then(|t| t.future.take()))
+        else {
+            continue;
+        };
+        let waker = Waker::from(Arc::new(TaskWaker {
+            id,
+            woken: woken.clone(),
+        }));
+        match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+            Poll::Ready(()) => cancel(id),
+            Poll::Pending => TASKS.with_borrow_mut(|tasks| {
+                if let Some(task) = tasks.get_mut(id) {
+                    task.future = Some(future);
+                }
+            }),
+        }
+    }
+}
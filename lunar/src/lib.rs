@@ -3,26 +3,47 @@
 
 mod app;
 mod ctx;
+mod executor;
+mod reactive;
+mod root;
+mod signal_future;
 mod system;
 mod util;
 mod view;
 
 use std::rc::Rc;
 
-pub use app::{App, start};
+#[doc(hidden)]
+pub mod __macro {
+    pub use crate::view::element::prop::arg::*;
+}
+
+pub use app::{App, AppGuard, start};
 pub use ctx::Context;
 pub use either;
 pub use lunar_macro::view;
+pub use reactive::{Reactive, Subscription};
+pub use root::{LunarRoot, RunMode};
+pub use signal_future::{SignalFuture, signal_future};
+pub use system::batch;
+pub use view::element::{props, signals};
+#[cfg(feature = "serde")]
+pub use view::stateful::persisted::stateful_persisted_json;
 pub use view::{
     AnchorType, View, ViewId,
     any::{AnyView, AnyViewState},
+    computed::{Compute, ComputeViewState, Computed, computed},
     either::EitherViewState,
     element::{
         Element, ElementView, ElementViewState,
         attr::{Attr, AttrViewState},
+        attr_bind::{AttrBind, AttrBindViewState},
+        bind::{Bind, BindViewState},
         el,
         node_ref::{NodeRef, NodeRefViewState},
         on_signal::{OnSignal, OnSignalViewState},
+        on_typed::{OnTyped, OnTypedViewState, TypedCallback},
+        prop::{Prop, PropOpt, PropOptViewState, PropViewState},
         theme_override::{
             ThemeOverride, ThemeOverrideColor, ThemeOverrideConstant, ThemeOverrideFont,
             ThemeOverrideFontSize, ThemeOverrideIcon, ThemeOverrideStylebox, ThemeOverrideType,
@@ -39,6 +60,21 @@ pub use view::{
     on_rebuild::{OnRebuild, on_rebuild},
     on_teardown::{OnTeardown, on_teardown},
     option::OptionViewState,
-    stateful::{Stateful, StatefulViewState, state::State, stateful, stateful_quiet},
+    provide::{Provide, ProvideViewState, provide, use_context},
+    stateful::{
+        Stateful, StatefulViewState,
+        history::{History, HistoryState, stateful_history},
+        persisted::stateful_persisted,
+        reducer::{
+            Dispatch, Middleware, Reducer, ReducerData, StatefulReducer, StatefulReducerViewState,
+            stateful_reducer,
+        },
+        sender::StateSender,
+        state::{State, StateError},
+        stateful, stateful_quiet,
+        sub_state::SubState,
+    },
+    store::{Store, UseStore, UseStoreViewState, use_store},
+    task::{Task, TaskViewState, task},
     when::{When, WhenViewState, when},
 };
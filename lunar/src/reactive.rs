@@ -0,0 +1,174 @@
+use std::rc::Rc;
+
+use crate::{
+    Computed, HistoryState, Reducer, State, Store, SubState,
+    system::{SubscriptionId, mark_binding, subscribe, unsubscribe},
+};
+
+/// A value that can be read and watched for changes, like a `State` or a `Computed`.
+pub trait Reactive<T: 'static>: Clone + 'static {
+    fn read<R>(&self, f: impl FnOnce(&T) -> R) -> R;
+    /// Calls `cb` from `App::run` every time the value changes, until the returned
+    /// subscription is dropped.
+    fn watch(&self, cb: impl Fn() + 'static) -> Subscription;
+    /// Whether `other` reads the same value, so a watch on one also covers the other.
+    fn same_source(&self, other: &Self) -> bool;
+}
+
+impl<T: 'static> Reactive<T> for State<T> {
+    fn read<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.with(f)
+    }
+    fn watch(&self, cb: impl Fn() + 'static) -> Subscription {
+        Subscription {
+            id: subscribe(self.state_id, Rc::new(cb)),
+        }
+    }
+    fn same_source(&self, other: &Self) -> bool {
+        self.state_id == other.state_id
+    }
+}
+
+impl<T: 'static> Reactive<T> for Computed<T> {
+    fn read<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.with(f)
+    }
+    fn watch(&self, cb: impl Fn() + 'static) -> Subscription {
+        Subscription {
+            id: subscribe(self.state.state_id, Rc::new(cb)),
+        }
+    }
+    fn same_source(&self, other: &Self) -> bool {
+        self.state.state_id == other.state.state_id
+    }
+}
+
+impl<T: 'static> Reactive<T> for Store<T> {
+    fn read<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.state.read(f)
+    }
+    fn watch(&self, cb: impl Fn() + 'static) -> Subscription {
+        self.state.watch(cb)
+    }
+    fn same_source(&self, other: &Self) -> bool {
+        self.state.same_source(&other.state)
+    }
+}
+
+impl<U: 'static> Reactive<U> for SubState<U> {
+    fn read<R>(&self, f: impl FnOnce(&U) -> R) -> R {
+        self.with(f)
+    }
+    fn watch(&self, cb: impl Fn() + 'static) -> Subscription {
+        Subscription {
+            id: subscribe(self.state_id, Rc::new(cb)),
+        }
+    }
+    fn same_source(&self, other: &Self) -> bool {
+        self.state_id == other.state_id && Rc::ptr_eq(&self.lens, &other.lens)
+    }
+}
+
+impl<T: Clone + 'static> Reactive<T> for HistoryState<T> {
+    fn read<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.with(f)
+    }
+    fn watch(&self, cb: impl Fn() + 'static) -> Subscription {
+        self.state.watch(cb)
+    }
+    fn same_source(&self, other: &Self) -> bool {
+        self.state.same_source(&other.state)
+    }
+}
+
+impl<T: 'static, A: 'static> Reactive<T> for Reducer<T, A> {
+    fn read<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.with(f)
+    }
+    fn watch(&self, cb: impl Fn() + 'static) -> Subscription {
+        self.state.watch(cb)
+    }
+    fn same_source(&self, other: &Self) -> bool {
+        self.state.same_source(&other.state)
+    }
+}
+
+/// Unsubscribes when dropped.
+#[must_use = "dropping the Subscription unsubscribes immediately"]
+pub struct Subscription {
+    id: Option<SubscriptionId>,
+}
+impl Subscription {
+    /// Keeps the subscription alive until its state is freed.
+    pub fn detach(mut self) {
+        self.id = None;
+    }
+    /// Marks the watch as keeping a node up to date by itself, so a change only it sees
+    /// doesn't rebuild the state's subtree.
+    pub(crate) fn binding(self) -> Self {
+        if let Some(id) = self.id {
+            mark_binding(id);
+        }
+        self
+    }
+}
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            unsubscribe(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::Reactive;
+    use crate::system::{
+        free_state,
+        testing::{app, run_changed, state},
+    };
+
+    #[test]
+    fn subscribers_run_once_per_change_until_dropped() {
+        let app = app();
+        let count = state(app, &[], 0, None);
+        let seen = Rc::new(RefCell::new(vec![]));
+        let sub = count.subscribe({
+            let seen = seen.clone();
+            move |v| seen.borrow_mut().push(*v)
+        });
+
+        count.set(1);
+        count.set(2);
+        run_changed(app);
+        assert_eq!(*seen.borrow(), [2]);
+
+        drop(sub);
+        count.set(3);
+        run_changed(app);
+        assert_eq!(*seen.borrow(), [2]);
+    }
+
+    #[test]
+    fn detached_subscriptions_last_until_the_state_is_freed() {
+        let app = app();
+        let count = state(app, &[], 0, None);
+        let runs = Rc::new(RefCell::new(0));
+        count
+            .watch({
+                let runs = runs.clone();
+                move || *runs.borrow_mut() += 1
+            })
+            .detach();
+
+        count.set(1);
+        run_changed(app);
+        assert_eq!(*runs.borrow(), 1);
+
+        drop(free_state(count.state_id));
+        assert_eq!(Rc::strong_count(&runs), 1);
+        assert!(count.watch(|| {}).id.is_none());
+    }
+}
@@ -0,0 +1,139 @@
+use godot::{
+    builtin::Callable,
+    classes::{INode, Node},
+    obj::{Base, Gd, WithBaseField},
+    prelude::{GodotClass, godot_api},
+};
+
+use crate::app::App;
+
+/// When a [`LunarRoot`] drains the notifications of its app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RunMode {
+    /// Every `_process` of the runner node.
+    #[default]
+    Process,
+    /// Every `_physics_process` of the runner node.
+    PhysicsProcess,
+    /// On the scene tree's `process_frame` signal, before any node is processed.
+    IdleFrame,
+    /// Deferred to the end of every frame, after all `_process` callbacks ran.
+    Deferred,
+}
+
+/// Drives an [`App`] on its own and stops it once the node leaves the tree.
+///
+/// Created with [`App::attach`].
+#[derive(GodotClass)]
+#[class(base=Node, no_init)]
+pub struct LunarRoot {
+    base: Base<Node>,
+
+    app: Option<App>,
+    mode: RunMode,
+    run_callable: Option<Callable>,
+}
+
+#[godot_api]
+impl INode for LunarRoot {
+    fn ready(&mut self) {
+        let mode = self.mode;
+        self.base_mut()
+            .set_process(matches!(mode, RunMode::Process | RunMode::Deferred));
+        self.base_mut()
+            .set_physics_process(mode == RunMode::PhysicsProcess);
+    }
+
+    fn enter_tree(&mut self) {
+        if self.mode == RunMode::IdleFrame {
+            let callable = self.run_callable();
+            if let Some(mut tree) = self.base().get_tree()
+                && !tree.is_connected("process_frame", &callable)
+            {
+                tree.connect("process_frame", &callable);
+            }
+        }
+    }
+
+    fn process(&mut self, _delta: f64) {
+        match self.mode {
+            RunMode::Process => self.run(),
+            RunMode::Deferred => self.run_callable().call_deferred(&[]),
+            _ => {}
+        }
+    }
+
+    fn physics_process(&mut self, _delta: f64) {
+        self.run();
+    }
+
+    fn exit_tree(&mut self) {
+        if let Some(callable) = &self.run_callable
+            && let Some(mut tree) = self.base().get_tree()
+            && tree.is_connected("process_frame", callable)
+        {
+            tree.disconnect("process_frame", callable);
+        }
+
+        // the parent is still busy removing its children here, so the views
+        // can only be torn down once the tree is done. By then the node might
+        // just have been moved somewhere else, then it keeps running.
+        if let Some(app) = self.app.as_ref().map(App::handle) {
+            let node = self.to_gd();
+            Callable::from_fn("lunar_stop", move |_| {
+                if node.is_instance_valid() {
+                    if node.is_inside_tree() {
+                        return;
+                    }
+                    node.clone().bind_mut().app = None;
+                }
+                app.stop();
+            })
+            .call_deferred(&[]);
+        }
+    }
+}
+
+impl LunarRoot {
+    fn run(&mut self) {
+        if let Some(app) = &self.app {
+            app.run();
+        }
+    }
+
+    fn run_callable(&mut self) -> Callable {
+        let node = self.to_gd();
+        self.run_callable
+            .get_or_insert_with(|| {
+                Callable::from_fn("lunar_run", move |_| {
+                    if node.is_instance_valid() {
+                        node.clone().bind_mut().run();
+                    }
+                })
+            })
+            .clone()
+    }
+
+    pub fn app(&self) -> Option<&App> {
+        self.app.as_ref()
+    }
+    pub fn mode(&self) -> RunMode {
+        self.mode
+    }
+}
+
+impl App {
+    /// Adds a [`LunarRoot`] under the app's root node which runs the app according
+    /// to `mode` and stops it when the runner leaves the tree.
+    pub fn attach(self, mode: RunMode) -> Gd<LunarRoot> {
+        let mut root = self.root();
+        let node = Gd::from_init_fn(|base| LunarRoot {
+            base,
+            app: Some(self),
+            mode,
+            run_callable: None,
+        });
+        root.add_child(&node);
+        node
+    }
+}
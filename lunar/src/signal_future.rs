@@ -0,0 +1,80 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use godot::{
+    builtin::{Callable, StringName, Variant},
+    classes::{Object, object::ConnectFlags},
+    obj::{Gd, Inherits},
+};
+
+#[derive(Default)]
+struct Shared {
+    args: Option<Vec<Variant>>,
+    waker: Option<Waker>,
+}
+
+/// Resolves with the arguments of the next emission of a signal.
+///
+/// Dropping it before the signal fires disconnects it again.
+pub struct SignalFuture {
+    object: Gd<Object>,
+    signal: StringName,
+    callable: Callable,
+    shared: Rc<RefCell<Shared>>,
+}
+
+pub fn signal_future<O, Name>(object: &Gd<O>, signal: Name) -> SignalFuture
+where
+    O: Inherits<Object>,
+    Name: AsRef<str>,
+{
+    let mut object = object.clone().upcast::<Object>();
+    let signal = StringName::from(signal.as_ref());
+    let shared = Rc::new(RefCell::new(Shared::default()));
+
+    let cb_shared = shared.clone();
+    let callable = Callable::from_fn("signal_future", move |args| {
+        let mut shared = cb_shared.borrow_mut();
+        shared.args = Some(args.iter().map(|v| (*v).clone()).collect());
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    });
+    object.connect_flags(&signal, &callable, ConnectFlags::ONE_SHOT);
+
+    SignalFuture {
+        object,
+        signal,
+        callable,
+        shared,
+    }
+}
+
+impl Future for SignalFuture {
+    type Output = Vec<Variant>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.borrow_mut();
+        match shared.args.take() {
+            Some(args) => Poll::Ready(args),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for SignalFuture {
+    fn drop(&mut self) {
+        if self.object.is_instance_valid() && self.object.is_connected(&self.signal, &self.callable)
+        {
+            self.object.disconnect(&self.signal, &self.callable);
+        }
+    }
+}
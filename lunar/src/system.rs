@@ -1,6 +1,19 @@
-use std::{any::Any, cell::RefCell, mem::ManuallyDrop, rc::Rc};
+use std::{
+    any::{Any, TypeId},
+    cell::{Cell, RefCell},
+    future::Future,
+    mem::ManuallyDrop,
+    pin::Pin,
+    rc::Rc,
+    sync::{
+        Arc,
+        mpsc::{Receiver, Sender},
+    },
+};
 
+use ahash::AHashMap;
 use godot::{classes::Node, obj::Gd};
+use parking_lot::Mutex;
 use slotmap::{SecondaryMap, SlotMap, new_key_type};
 
 use crate::{
@@ -14,11 +27,84 @@ use crate::{
 new_key_type! {
     pub struct StateId;
     pub struct AppId;
+    pub struct TaskId;
+    pub struct SubscriptionId;
 }
 
 pub struct StateData {
     pub value: Rc<RefCell<dyn Any>>,
     pub path: Rc<[ViewId]>,
+    pub app_id: AppId,
+    /// Computed states that read this one during their last evaluation.
+    pub dependents: Vec<StateId>,
+    pub subscribers: Vec<SubscriptionId>,
+    /// If set, writes that leave the value equal don't notify.
+    pub eq: Option<EqPolicy>,
+    pub created: Created,
+    /// Whether a view read the state while being built since its subtree last rebuilt.
+    /// If not, a change only bindings and computed states see leaves the subtree alone.
+    pub view_read: bool,
+}
+
+/// Where a state was created, only tracked in debug builds.
+#[cfg(debug_assertions)]
+pub type Created = &'static std::panic::Location<'static>;
+#[cfg(not(debug_assertions))]
+pub type Created = ();
+
+#[track_caller]
+pub fn caller() -> Created {
+    #[cfg(debug_assertions)]
+    {
+        std::panic::Location::caller()
+    }
+}
+
+/// Formats `created` to be appended to a message, empty in release builds.
+#[allow(unused_variables)]
+pub fn describe_created(created: Created) -> String {
+    #[cfg(debug_assertions)]
+    {
+        format!(" (created at {created})")
+    }
+    #[cfg(not(debug_assertions))]
+    {
+        String::new()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct EqPolicy {
+    pub eq: fn(&dyn Any, &dyn Any) -> bool,
+    pub clone: fn(&dyn Any) -> Box<dyn Any>,
+}
+impl EqPolicy {
+    pub fn of<T: PartialEq + Clone + 'static>() -> Self {
+        Self {
+            eq: |a, b| a.downcast_ref::<T>() == b.downcast_ref::<T>(),
+            clone: |v| Box::new(v.downcast_ref::<T>().unwrap().clone()),
+        }
+    }
+}
+
+pub struct SubscriptionData {
+    pub state_id: StateId,
+    pub cb: Rc<dyn Fn()>,
+    /// Set for the watch of a `name <- source` binding, which updates its node by itself.
+    pub binding: bool,
+}
+
+/// A state owned by the app rather than a view. Notifying it rebuilds the views reading it.
+pub struct StoreData {
+    pub type_id: TypeId,
+    pub readers: Vec<Rc<[ViewId]>>,
+}
+
+pub struct ComputedData {
+    /// Reevaluates into the given cell, returns whether the value changed.
+    pub compute: Rc<dyn Fn(&RefCell<dyn Any>) -> bool>,
+    pub deps: Vec<StateId>,
+    pub dirty: bool,
 }
 
 pub struct AppData {
@@ -26,10 +112,501 @@ pub struct AppData {
     pub view: Rc<RefCell<Box<dyn AnyView>>>,
     pub view_state: Rc<RefCell<AnyViewState>>,
     pub root: Gd<Node>,
+    pub max_iterations: usize,
+    /// Set while `App::run` is on the stack.
+    pub running: bool,
+    /// A stop that came in while running, done once `run` returns.
+    pub stop_requested: bool,
+}
+
+/// The paths of the `Stateful`s waiting to be rebuilt.
+///
+/// A path stays queued until its `Stateful` actually reruns, so a path covered by the
+/// rebuild of an ancestor is dropped without being replayed, while one hidden behind a
+/// `memo` that skipped its subtree is still processed.
+#[derive(Default)]
+pub struct Notifications {
+    paths: AHashMap<Rc<[ViewId]>, StateId>,
+    dirty: Vec<StateId>,
+    changed: Vec<StateId>,
+}
+impl Notifications {
+    pub fn push(&mut self, path: Rc<[ViewId]>, state_id: StateId) {
+        self.paths.insert(path, state_id);
+    }
+    pub fn remove(&mut self, path: &[ViewId]) -> bool {
+        self.paths.remove(path).is_some()
+    }
+    /// The queued paths, ancestors first.
+    pub fn sorted(&self) -> Vec<Rc<[ViewId]>> {
+        let mut paths = self.paths.keys().cloned().collect::<Vec<_>>();
+        paths.sort_by_key(|p| p.len());
+        paths
+    }
+    pub fn states(&self) -> impl Iterator<Item = (&[ViewId], StateId)> {
+        self.paths.iter().map(|(path, id)| (&**path, *id))
+    }
+    pub fn take_dirty(&mut self) -> Vec<StateId> {
+        std::mem::take(&mut self.dirty)
+    }
+    /// The states with subscribers that changed, each one once.
+    pub fn take_changed(&mut self) -> Vec<StateId> {
+        let mut changed = std::mem::take(&mut self.changed);
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+    pub fn is_settled(&self) -> bool {
+        self.paths.is_empty() && self.dirty.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Work sent from other threads, run on the main thread by `App::run`.
+pub type AppMessage = Box<dyn FnOnce() + Send>;
+
+pub struct AppChannel {
+    pub sender: Sender<AppMessage>,
+    pub receiver: Receiver<AppMessage>,
+    pub woken: Arc<Mutex<Vec<TaskId>>>,
+}
+
+pub struct TaskData {
+    pub app_id: AppId,
+    pub future: Option<Pin<Box<dyn Future<Output = ()>>>>,
+}
+
+/// The app being built or run right now, if any.
+pub fn current_app() -> Option<AppId> {
+    CURRENT_APP.get()
+}
+
+pub fn with_current_app<R>(app_id: AppId, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<AppId>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT_APP.set(self.0);
+        }
+    }
+    let _restore = Restore(CURRENT_APP.replace(Some(app_id)));
+    f()
+}
+
+pub fn mark_rebuilt(app_id: AppId, path: &[ViewId]) {
+    APP_NOTIFICATIONS.with_borrow_mut(|map| {
+        if let Some(notifs) = map.get_mut(app_id) {
+            notifs.remove(path);
+        }
+    });
+}
+
+/// Runs `f`, holding back the notifications of every state it changes until it returns.
+///
+/// Each state is then notified once, no matter how often it was written to. Batches can
+/// be nested, only the outermost one flushes.
+pub fn batch<R>(f: impl FnOnce() -> R) -> R {
+    struct Flush;
+    impl Drop for Flush {
+        fn drop(&mut self) {
+            let depth = BATCH_DEPTH.get() - 1;
+            BATCH_DEPTH.set(depth);
+            if depth == 0 {
+                let mut batched = BATCHED.take();
+                // a state written through a loud handle anywhere in the batch is loud
+                batched.sort_by_key(|(id, quiet)| (*id, *quiet));
+                batched.dedup_by_key(|(id, _)| *id);
+                for (id, quiet) in batched {
+                    notify(id, quiet);
+                }
+            }
+        }
+    }
+    BATCH_DEPTH.set(BATCH_DEPTH.get() + 1);
+    let _flush = Flush;
+    f()
+}
+
+pub fn notify(state_id: StateId, quiet: bool) {
+    let Some((path, app_id, subscribed, view_read)) = STATES.with_borrow(|states| {
+        states.get(state_id).map(|s| {
+            (
+                s.path.clone(),
+                s.app_id,
+                !s.subscribers.is_empty(),
+                s.view_read,
+            )
+        })
+    }) else {
+        return;
+    };
+    // computed states read inside the batch should still see the new value
+    mark_dependents_dirty(state_id, app_id);
+    if BATCH_DEPTH.get() > 0 {
+        BATCHED.with_borrow_mut(|batched| batched.push((state_id, quiet)));
+        return;
+    }
+    let readers = STORES.with_borrow(|stores| stores.get(state_id).map(|s| s.readers.clone()));
+    // bindings and computed states keep up by themselves, only rebuild for views reading it
+    let quiet = quiet || (readers.is_none() && !view_read && read_elsewhere(state_id));
+    APP_NOTIFICATIONS.with_borrow_mut(|map| {
+        let notifs = map.entry(app_id).unwrap().or_default();
+        if subscribed {
+            notifs.changed.push(state_id);
+        }
+        if !quiet {
+            match readers {
+                Some(readers) => {
+                    for reader in readers {
+                        notifs.push(reader, state_id);
+                    }
+                }
+                None => notifs.push(path, state_id),
+            }
+        }
+    });
+}
+
+pub fn subscribe(state_id: StateId, cb: Rc<dyn Fn()>) -> Option<SubscriptionId> {
+    let id = SUBSCRIPTIONS.with_borrow_mut(|subs| {
+        subs.insert(SubscriptionData {
+            state_id,
+            cb,
+            binding: false,
+        })
+    });
+    let added = STATES.with_borrow_mut(|states| {
+        states
+            .get_mut(state_id)
+            .map(|data| data.subscribers.push(id))
+            .is_some()
+    });
+    if added {
+        Some(id)
+    } else {
+        let sub = SUBSCRIPTIONS.with_borrow_mut(|subs| subs.remove(id));
+        drop(sub);
+        None
+    }
+}
+
+pub fn unsubscribe(id: SubscriptionId) {
+    let Some(sub) = SUBSCRIPTIONS.with_borrow_mut(|subs| subs.remove(id)) else {
+        return;
+    };
+    STATES.with_borrow_mut(|states| {
+        if let Some(data) = states.get_mut(sub.state_id) {
+            data.subscribers.retain(|s| *s != id);
+        }
+    });
+}
+
+/// Whether a computed state or a binding reads the state.
+fn read_elsewhere(state_id: StateId) -> bool {
+    let Some((computed, subscribers)) = STATES.with_borrow(|states| {
+        states
+            .get(state_id)
+            .map(|s| (!s.dependents.is_empty(), s.subscribers.clone()))
+    }) else {
+        return false;
+    };
+    computed
+        || SUBSCRIPTIONS.with_borrow(|subs| {
+            subscribers
+                .iter()
+                .any(|id| subs.get(*id).is_some_and(|s| s.binding))
+        })
+}
+
+/// Marks a subscription as the watch of a binding.
+pub fn mark_binding(id: SubscriptionId) {
+    SUBSCRIPTIONS.with_borrow_mut(|subs| {
+        if let Some(sub) = subs.get_mut(id) {
+            sub.binding = true;
+        }
+    });
+}
+
+/// Calls the subscribers of a state that changed.
+pub fn run_subscribers(state_id: StateId) {
+    let subscribers = STATES.with_borrow(|states| {
+        states
+            .get(state_id)
+            .map(|s| s.subscribers.clone())
+            .unwrap_or_default()
+    });
+    let cbs = SUBSCRIPTIONS.with_borrow(|subs| {
+        subscribers
+            .iter()
+            .filter_map(|id| subs.get(*id).map(|s| s.cb.clone()))
+            .collect::<Vec<_>>()
+    });
+    for cb in cbs {
+        cb();
+    }
+}
+
+/// Marks every computed state that (indirectly) depends on `state_id` as dirty and
+/// queues it to be reevaluated by `App::run`.
+fn mark_dependents_dirty(state_id: StateId, app_id: AppId) {
+    let mut stack = vec![state_id];
+    while let Some(id) = stack.pop() {
+        let dependents = STATES.with_borrow(|states| {
+            states
+                .get(id)
+                .map(|s| s.dependents.clone())
+                .unwrap_or_default()
+        });
+        for dep in dependents {
+            let newly_dirty = COMPUTED.with_borrow_mut(|computed| {
+                computed
+                    .get_mut(dep)
+                    .is_some_and(|c| !std::mem::replace(&mut c.dirty, true))
+            });
+            if newly_dirty {
+                APP_NOTIFICATIONS
+                    .with_borrow_mut(|map| map.entry(app_id).unwrap().or_default().dirty.push(dep));
+                stack.push(dep);
+            }
+        }
+    }
+}
+
+pub fn track_read(state_id: StateId) {
+    let computing = TRACKING
+        .with_borrow_mut(|stack| stack.last_mut().map(|reads| reads.push(state_id)).is_some());
+    if !computing && BUILDING.get() {
+        STATES.with_borrow_mut(|states| {
+            if let Some(data) = states.get_mut(state_id) {
+                data.view_read = true;
+            }
+        });
+    }
+}
+
+/// Runs `f` with reads counting as reads by the views being built, or not.
+pub fn building<R>(on: bool, f: impl FnOnce() -> R) -> R {
+    let prev = BUILDING.replace(on);
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            BUILDING.set(self.0);
+        }
+    }
+    let _restore = Restore(prev);
+    f()
+}
+
+/// Forgets the reads of a state by views, before its subtree rebuilds and reads it again.
+pub fn clear_view_reads(state_id: StateId) {
+    STATES.with_borrow_mut(|states| {
+        if let Some(data) = states.get_mut(state_id) {
+            data.view_read = false;
+        }
+    });
+}
+
+/// Runs `f`, recording every state it reads.
+pub fn tracked<R>(f: impl FnOnce() -> R) -> (R, Vec<StateId>) {
+    TRACKING.with_borrow_mut(|stack| stack.push(vec![]));
+    let out = f();
+    let mut reads = TRACKING.with_borrow_mut(|stack| stack.pop().unwrap());
+    reads.sort();
+    reads.dedup();
+    (out, reads)
+}
+
+/// Removes a state and everything attached to it. The data is returned so it gets
+/// dropped outside of the borrow.
+pub fn free_state(state_id: StateId) -> Option<StateData> {
+    if let Some(computed) = COMPUTED.with_borrow_mut(|computed| computed.remove(state_id)) {
+        set_deps(state_id, &computed.deps, &[]);
+    }
+    let data = STATES.with_borrow_mut(|states| states.remove(state_id))?;
+    if let Some(store) = STORES.with_borrow_mut(|stores| stores.remove(state_id)) {
+        APP_STORES.with_borrow_mut(|map| map.remove(&(data.app_id, store.type_id)));
+    }
+    let subs = SUBSCRIPTIONS.with_borrow_mut(|subs| {
+        data.subscribers
+            .iter()
+            .filter_map(|id| subs.remove(*id))
+            .collect::<Vec<_>>()
+    });
+    drop(subs);
+    Some(data)
+}
+
+/// Moves a computed state from the `dependents` of `old` to the ones of `new`.
+pub fn set_deps(state_id: StateId, old: &[StateId], new: &[StateId]) {
+    STATES.with_borrow_mut(|states| {
+        for dep in old {
+            if let Some(data) = states.get_mut(*dep) {
+                data.dependents.retain(|d| *d != state_id);
+            }
+        }
+        for dep in new {
+            if let Some(data) = states.get_mut(*dep) {
+                data.dependents.push(state_id);
+            }
+        }
+    });
 }
 
 thread_local! {
     pub static STATES: RefCell<ManuallyDrop<SlotMap<StateId, StateData>>> = RefCell::new(ManuallyDrop::new(SlotMap::default()));
+    pub static COMPUTED: RefCell<ManuallyDrop<SecondaryMap<StateId, ComputedData>>> = RefCell::new(ManuallyDrop::new(SecondaryMap::new()));
+    pub static SUBSCRIPTIONS: RefCell<ManuallyDrop<SlotMap<SubscriptionId, SubscriptionData>>> = RefCell::new(ManuallyDrop::new(SlotMap::default()));
+    pub static STORES: RefCell<ManuallyDrop<SecondaryMap<StateId, StoreData>>> = RefCell::new(ManuallyDrop::new(SecondaryMap::new()));
+    pub static APP_STORES: RefCell<AHashMap<(AppId, TypeId), StateId>> = RefCell::new(AHashMap::new());
+    pub static CURRENT_APP: Cell<Option<AppId>> = const { Cell::new(None) };
+    pub static BATCH_DEPTH: Cell<usize> = const { Cell::new(0) };
+    pub static BATCHED: RefCell<Vec<(StateId, bool)>> = const { RefCell::new(vec![]) };
+    pub static TRACKING: RefCell<Vec<Vec<StateId>>> = const { RefCell::new(vec![]) };
+    /// Whether views are being built, so reads outside a computed count as view reads.
+    pub static BUILDING: Cell<bool> = const { Cell::new(false) };
+    /// Values of the `provide` views above the view currently being built.
+    pub static PROVIDERS: RefCell<Vec<(TypeId, Rc<dyn Any>)>> = const { RefCell::new(vec![]) };
     pub static APPS: RefCell<ManuallyDrop<SlotMap<AppId, AppData>>> = RefCell::new(ManuallyDrop::new(SlotMap::default()));
-    pub static APP_NOTIFICATIONS: RefCell<ManuallyDrop<SecondaryMap<AppId, Vec<Rc<[ViewId]>>>>> = RefCell::new(ManuallyDrop::new(SecondaryMap::new()));
+    pub static TASKS: RefCell<ManuallyDrop<SlotMap<TaskId, TaskData>>> = RefCell::new(ManuallyDrop::new(SlotMap::default()));
+    pub static APP_CHANNELS: RefCell<ManuallyDrop<SecondaryMap<AppId, AppChannel>>> = RefCell::new(ManuallyDrop::new(SecondaryMap::new()));
+    pub static APP_NOTIFICATIONS: RefCell<ManuallyDrop<SecondaryMap<AppId, Notifications>>> = RefCell::new(ManuallyDrop::new(SecondaryMap::new()));
+}
+
+/// States outside of any view, for tests that don't need the engine.
+#[cfg(test)]
+pub mod testing {
+    use std::marker::PhantomData;
+
+    use super::*;
+    use crate::State;
+
+    thread_local! {
+        static TEST_APPS: RefCell<SlotMap<AppId, ()>> = RefCell::new(SlotMap::default());
+    }
+
+    /// An id for an app without a view tree.
+    pub fn app() -> AppId {
+        TEST_APPS.with_borrow_mut(|apps| apps.insert(()))
+    }
+
+    /// A state like the one a `stateful` at `path` makes.
+    #[track_caller]
+    pub fn state<T: 'static>(
+        app_id: AppId,
+        path: &[ViewId],
+        value: T,
+        eq: Option<EqPolicy>,
+    ) -> State<T> {
+        let created = caller();
+        let state_id = STATES.with_borrow_mut(|states| {
+            states.insert(StateData {
+                value: Rc::new(RefCell::new(value)),
+                path: path.into(),
+                app_id,
+                dependents: vec![],
+                subscribers: vec![],
+                eq,
+                created,
+                view_read: false,
+            })
+        });
+        State {
+            state_id,
+            app_id,
+            quiet: false,
+            created,
+            _p: PhantomData,
+        }
+    }
+
+    /// Calls the subscribers of the states that changed, like `App::run` does.
+    pub fn run_changed(app_id: AppId) {
+        let changed = APP_NOTIFICATIONS
+            .with_borrow_mut(|map| map.get_mut(app_id).map(|n| n.take_changed()))
+            .unwrap_or_default();
+        for id in changed {
+            run_subscribers(id);
+        }
+    }
+
+    /// Takes the paths queued for rebuilding, ancestors first.
+    pub fn take_queued(app_id: AppId) -> Vec<Rc<[ViewId]>> {
+        APP_NOTIFICATIONS.with_borrow_mut(|map| {
+            let Some(notifs) = map.get_mut(app_id) else {
+                return vec![];
+            };
+            let paths = notifs.sorted();
+            for path in &paths {
+                notifs.remove(path);
+            }
+            paths
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        batch, building, clear_view_reads, set_deps,
+        testing::{app, state, take_queued},
+    };
+    use crate::{Reactive, view::ViewId};
+
+    #[test]
+    fn notifying_twice_queues_the_path_once() {
+        let app = app();
+        let path = [ViewId::Structural(0), ViewId::Key(1)];
+        let count = state(app, &path, 0, None);
+        let other = state(app, &path[..1], 0, None);
+        count.notify();
+        count.notify();
+        other.notify();
+        assert_eq!(
+            take_queued(app),
+            [path[..1].into(), path.into()] as [std::rc::Rc<[ViewId]>; 2]
+        );
+        assert!(take_queued(app).is_empty());
+    }
+
+    #[test]
+    fn batches_notify_once_when_they_end() {
+        let app = app();
+        let count = state(app, &[ViewId::Structural(0)], 0, None);
+        batch(|| {
+            count.set(1);
+            batch(|| count.set(2));
+            assert!(take_queued(app).is_empty());
+        });
+        assert_eq!(take_queued(app).len(), 1);
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn bound_states_rebuild_only_for_view_reads() {
+        let app = app();
+        let text = state(app, &[ViewId::Structural(0)], String::new(), None);
+        let _binding = text.watch(|| {}).binding();
+        text.set("a".into());
+        assert!(take_queued(app).is_empty());
+
+        building(true, || text.get_clone());
+        text.set("b".into());
+        assert_eq!(take_queued(app).len(), 1);
+
+        // the next rebuild didn't read it
+        clear_view_reads(text.state_id);
+        text.set("c".into());
+        assert!(take_queued(app).is_empty());
+    }
+
+    #[test]
+    fn states_only_computed_states_read_skip_the_rebuild() {
+        let app = app();
+        let count = state(app, &[ViewId::Structural(0)], 0, None);
+        count.set(1);
+        assert_eq!(take_queued(app).len(), 1);
+
+        let doubled = state(app, &[ViewId::Structural(0)], 0, None);
+        set_deps(doubled.state_id, &[], &[count.state_id]);
+        count.set(2);
+        assert!(take_queued(app).is_empty());
+    }
 }
@@ -53,6 +53,12 @@ pub struct AnyViewState {
     id: ViewId,
 }
 
+impl AnyViewState {
+    pub(crate) fn anchor(&self) -> &Gd<Node> {
+        &self.anchor
+    }
+}
+
 // MARK: AnyView for View
 
 impl<V> AnyView for V
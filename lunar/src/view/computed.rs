@@ -0,0 +1,315 @@
+use std::{any::Any, cell::RefCell, marker::PhantomData, rc::Rc};
+
+use crate::{
+    State, StateError,
+    system::{
+        COMPUTED, ComputedData, Created, STATES, StateData, StateId, caller, clear_view_reads,
+        free_state, mark_rebuilt, notify, set_deps, tracked,
+    },
+    view::{View, ViewId},
+};
+
+/// A read-only state derived from other states.
+///
+/// Every state read while evaluating it is tracked, and it is only reevaluated after one
+/// of them changed. Its subtree is rebuilt when the new value differs from the old one,
+/// unless only `name <- computed` bindings read it.
+pub struct Computed<T: 'static> {
+    pub(crate) state: State<T>,
+}
+impl<T> Copy for Computed<T> {}
+impl<T> Clone for Computed<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Computed<T> {
+    fn refresh(&self) {
+        refresh(self.state.state_id);
+    }
+    pub fn is_valid(&self) -> bool {
+        self.state.is_valid()
+    }
+    pub fn try_with<R, F>(&self, f: F) -> Result<R, StateError>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.refresh();
+        self.state.try_with(f)
+    }
+    pub fn try_get(&self) -> Result<T, StateError>
+    where
+        T: Copy,
+    {
+        self.try_with(|v| *v)
+    }
+    pub fn try_get_clone(&self) -> Result<T, StateError>
+    where
+        T: Clone,
+    {
+        self.try_with(|v| v.clone())
+    }
+    #[track_caller]
+    pub fn get(&self) -> T
+    where
+        T: Copy,
+    {
+        self.refresh();
+        self.state.get()
+    }
+    #[track_caller]
+    pub fn get_clone(&self) -> T
+    where
+        T: Clone,
+    {
+        self.refresh();
+        self.state.get_clone()
+    }
+    #[track_caller]
+    pub fn with<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.refresh();
+        self.state.with(f)
+    }
+}
+
+/// Reevaluates a computed state, returns whether its value changed.
+fn recompute(state_id: StateId) -> bool {
+    let Some((compute, old_deps)) = COMPUTED.with_borrow(|computed| {
+        computed
+            .get(state_id)
+            .map(|c| (c.compute.clone(), c.deps.clone()))
+    }) else {
+        return false;
+    };
+    let Some(value) = STATES.with_borrow(|states| states.get(state_id).map(|s| s.value.clone()))
+    else {
+        return false;
+    };
+    let (changed, deps) = tracked(|| compute(&value));
+    set_deps(state_id, &old_deps, &deps);
+    COMPUTED.with_borrow_mut(|computed| {
+        if let Some(c) = computed.get_mut(state_id) {
+            c.deps = deps;
+            c.dirty = false;
+        }
+    });
+    changed
+}
+
+/// Reevaluates a computed state if one of its dependencies changed.
+pub(crate) fn refresh(state_id: StateId) {
+    let dirty = COMPUTED.with_borrow(|computed| computed.get(state_id).is_some_and(|c| c.dirty));
+    if dirty && recompute(state_id) {
+        notify(state_id, false);
+    }
+}
+
+fn make_compute<T, ComputeFn>(f: Rc<ComputeFn>) -> Rc<dyn Fn(&RefCell<dyn Any>) -> bool>
+where
+    T: PartialEq + 'static,
+    ComputeFn: Fn() -> T + 'static,
+{
+    Rc::new(move |cell: &RefCell<dyn Any>| {
+        let new = f();
+        let mut cell = cell.borrow_mut();
+        let old = cell.downcast_mut::<T>().unwrap();
+        if *old != new {
+            *old = new;
+            true
+        } else {
+            false
+        }
+    })
+}
+
+pub struct Compute<ComputeFn, InnerFn> {
+    compute_fn: Rc<ComputeFn>,
+    inner_fn: InnerFn,
+    created: Created,
+}
+pub struct ComputeViewState<T: 'static, Inner: View> {
+    computed: Computed<T>,
+    path: Rc<[ViewId]>,
+    inner: Inner,
+    inner_state: Inner::ViewState,
+    inner_id: ViewId,
+}
+
+impl<ComputeFn, InnerFn, T, Inner> View for Compute<ComputeFn, InnerFn>
+where
+    T: PartialEq + 'static,
+    ComputeFn: Fn() -> T + 'static,
+    InnerFn: Fn(Computed<T>) -> Inner,
+    Inner: View,
+{
+    type ViewState = ComputeViewState<T, Inner>;
+
+    fn build(
+        &self,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) -> Self::ViewState {
+        let (value, deps) = tracked(|| (self.compute_fn)());
+        let path: Rc<[ViewId]> = ctx.path.clone().into();
+        let id = STATES.with_borrow_mut(|states| {
+            states.insert(StateData {
+                value: Rc::new(RefCell::new(value)),
+                path: path.clone(),
+                app_id: ctx.app_id,
+                dependents: vec![],
+                subscribers: vec![],
+                eq: None,
+                created: self.created,
+                view_read: false,
+            })
+        });
+        set_deps(id, &[], &deps);
+        COMPUTED.with_borrow_mut(|computed| {
+            computed.insert(
+                id,
+                ComputedData {
+                    compute: make_compute(self.compute_fn.clone()),
+                    deps,
+                    dirty: false,
+                },
+            )
+        });
+        let computed = Computed {
+            state: State {
+                state_id: id,
+                app_id: ctx.app_id,
+                quiet: false,
+                created: self.created,
+                _p: PhantomData,
+            },
+        };
+        let inner = (self.inner_fn)(computed);
+        let inner_id = ctx.new_structural_id();
+        let inner_state = ctx.with_id(inner_id, |ctx| inner.build(ctx, anchor, anchor_type));
+        ComputeViewState {
+            computed,
+            path,
+            inner,
+            inner_state,
+            inner_id,
+        }
+    }
+
+    fn rebuild(
+        &self,
+        _prev: &Self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) {
+        // the new closure might capture different states
+        let id = state.computed.state.state_id;
+        COMPUTED.with_borrow_mut(|computed| {
+            if let Some(c) = computed.get_mut(id) {
+                c.compute = make_compute(self.compute_fn.clone());
+            }
+        });
+        if recompute(id) {
+            notify(id, false);
+        }
+
+        mark_rebuilt(ctx.app_id, &state.path);
+
+        clear_view_reads(state.computed.state.state_id);
+        let inner = (self.inner_fn)(state.computed);
+        ctx.with_id(state.inner_id, |ctx| {
+            inner.rebuild(
+                &state.inner,
+                &mut state.inner_state,
+                ctx,
+                anchor,
+                anchor_type,
+            );
+        });
+        state.inner = inner;
+    }
+
+    fn teardown(
+        &self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) {
+        ctx.with_id(state.inner_id, |ctx| {
+            state
+                .inner
+                .teardown(&mut state.inner_state, ctx, anchor, anchor_type);
+        });
+        free_state(state.computed.state.state_id);
+    }
+
+    fn notify_state(
+        &self,
+        path: &[ViewId],
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) {
+        if let Some((start, rest)) = path.split_first() {
+            if *start == state.inner_id {
+                ctx.with_id(state.inner_id, |ctx| {
+                    state.inner.notify_state(
+                        rest,
+                        &mut state.inner_state,
+                        ctx,
+                        anchor,
+                        anchor_type,
+                    );
+                });
+            }
+        } else {
+            mark_rebuilt(ctx.app_id, &state.path);
+            clear_view_reads(state.computed.state.state_id);
+            let new = (self.inner_fn)(state.computed);
+            ctx.with_id(state.inner_id, |ctx| {
+                new.rebuild(
+                    &state.inner,
+                    &mut state.inner_state,
+                    ctx,
+                    anchor,
+                    anchor_type,
+                );
+            });
+            state.inner = new;
+        }
+    }
+
+    fn collect_nodes(
+        &self,
+        state: &Self::ViewState,
+        nodes: &mut Vec<godot::prelude::Gd<godot::prelude::Node>>,
+    ) {
+        state.inner.collect_nodes(&state.inner_state, nodes);
+    }
+}
+
+#[track_caller]
+pub fn computed<T, Inner, ComputeFn, InnerFn>(
+    compute: ComputeFn,
+    view: InnerFn,
+) -> Compute<ComputeFn, InnerFn>
+where
+    T: PartialEq + 'static,
+    ComputeFn: Fn() -> T + 'static,
+    InnerFn: Fn(Computed<T>) -> Inner,
+    Inner: View,
+{
+    Compute {
+        compute_fn: Rc::new(compute),
+        inner_fn: view,
+        created: caller(),
+    }
+}
@@ -6,7 +6,7 @@ use godot::{
     obj::{Gd, NewAlloc},
 };
 
-use crate::{AnchorType, Context, View, ViewId};
+use crate::{AnchorType, Context, View, ViewId, view::with_anchor};
 
 pub struct EitherViewState<AViewState, BViewState> {
     anchor: Gd<Node>,
@@ -111,24 +111,21 @@ where
             state.inner.is_left(),
             "Bruh why are they not the same"
         );
-        let mut eit_anchor = state.anchor.clone();
-
-        match (self, &mut state.inner) {
+        with_anchor(&state.anchor, |eit_anchor| match (self, &mut state.inner) {
             (Left(val), Left(inner)) => {
                 ctx.with_id(state.id, |ctx| {
-                    val.teardown(inner, ctx, &mut eit_anchor, AnchorType::Before);
+                    val.teardown(inner, ctx, eit_anchor, AnchorType::Before);
                 });
             }
             (Right(val), Right(inner)) => {
                 ctx.with_id(state.id, |ctx| {
-                    val.teardown(inner, ctx, &mut eit_anchor, AnchorType::Before);
+                    val.teardown(inner, ctx, eit_anchor, AnchorType::Before);
                 });
             }
             _ => unreachable!(),
-        }
+        });
 
-        anchor_type.remove(anchor, &eit_anchor);
-        eit_anchor.queue_free();
+        anchor_type.remove_and_free(anchor, state.anchor.clone());
     }
 
     fn notify_state(
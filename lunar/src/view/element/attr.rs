@@ -1,4 +1,10 @@
-use godot::{builtin::Variant, classes::Node, meta::ToGodot, obj::Inherits, prelude::Gd};
+use godot::{
+    builtin::{StringName, Variant},
+    classes::{ClassDb, Node},
+    meta::ToGodot,
+    obj::{Inherits, Singleton},
+    prelude::Gd,
+};
 use std::marker::PhantomData;
 
 use crate::view::{
@@ -9,15 +15,29 @@ use crate::view::{
 pub struct Attr<N, Name, Inner, const BUILD_ONLY: bool> {
     pub(crate) inner: Inner,
     pub(crate) name: Name,
-    pub(crate) value: Variant,
+    /// `None` leaves the property as it was before Lunar set it.
+    pub(crate) value: Option<Variant>,
     pub(crate) _p: PhantomData<N>,
 }
 
 pub struct AttrViewState<InnerViewState> {
+    name: StringName,
+    /// The value from before Lunar touched the property.
     prev_value: Variant,
     inner_view_state: InnerViewState,
 }
 
+/// Puts back the value from before Lunar, or the class default if there was none.
+pub(crate) fn restore(node: &mut Gd<Node>, name: &StringName, prev_value: &Variant) {
+    if !prev_value.is_nil() {
+        node.set(name, prev_value);
+        return;
+    }
+    let class = StringName::from(&node.get_class());
+    let default = ClassDb::singleton().class_get_property_default_value(&class, name);
+    node.set(name, &default);
+}
+
 impl<N, Name, Inner, const BUILD_ONLY: bool> View for Attr<N, Name, Inner, BUILD_ONLY>
 where
     Inner: ElementView<N>,
@@ -34,9 +54,13 @@ where
     ) -> Self::ViewState {
         let inner_view_state = self.inner.build(ctx, anchor, anchor_type);
         let mut node = self.inner.get_node(&inner_view_state);
-        let prev_value = node.upcast_ref().get(self.name.as_ref());
-        node.upcast_mut().set(self.name.as_ref(), &self.value);
+        let name = StringName::from(self.name.as_ref());
+        let prev_value = node.upcast_ref().get(&name);
+        if let Some(value) = &self.value {
+            node.upcast_mut().set(&name, value);
+        }
         AttrViewState {
+            name,
             prev_value,
             inner_view_state,
         }
@@ -59,12 +83,26 @@ where
         );
 
         if !BUILD_ONLY {
-            let mut node = self.get_node(state);
-            if self.name.as_ref() != prev.name.as_ref() {
-                node.upcast_mut().set(prev.name.as_ref(), &state.prev_value);
+            let renamed = self.name.as_ref() != prev.name.as_ref();
+            let mut node = self.get_node(state).upcast::<Node>();
+            if renamed {
+                if prev.value.is_some() {
+                    restore(&mut node, &state.name, &state.prev_value);
+                }
+                state.name = StringName::from(self.name.as_ref());
+                state.prev_value = node.get(&state.name);
+            }
+            match &self.value {
+                // compared with the node rather than `prev`, so a control the user
+                // changed is put back
+                Some(value) if node.get(&state.name) != *value => node.set(&state.name, value),
+                Some(_) => {}
+                // the value was just taken away
+                None if !renamed && prev.value.is_some() => {
+                    restore(&mut node, &state.name, &state.prev_value)
+                }
+                None => {}
             }
-            state.prev_value = node.upcast_ref().get(self.name.as_ref());
-            node.upcast_mut().set(self.name.as_ref(), &self.value);
         }
     }
 
@@ -75,6 +113,10 @@ where
         anchor: &mut Node,
         anchor_type: AnchorType,
     ) {
+        let mut node = self.get_node(state).upcast::<Node>();
+        if self.value.is_some() && node.is_instance_valid() {
+            restore(&mut node, &state.name, &state.prev_value);
+        }
         self.inner
             .teardown(&mut state.inner_view_state, ctx, anchor, anchor_type);
     }
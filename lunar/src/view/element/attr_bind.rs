@@ -0,0 +1,157 @@
+use godot::{
+    builtin::{StringName, Variant},
+    classes::Node,
+    meta::ToGodot,
+    obj::Inherits,
+    prelude::Gd,
+};
+use std::marker::PhantomData;
+
+use crate::{
+    reactive::{Reactive, Subscription},
+    system::building,
+    view::{
+        AnchorType, View,
+        element::{ElementView, impl_element_view},
+    },
+};
+
+/// Sets a property from a `State` or `Computed` and keeps it in sync through a watch, so the
+/// property follows the value even where nothing rebuilds. A change only bindings read
+/// doesn't rebuild the views under the state, if a view reads it too they rebuild as usual.
+pub struct AttrBind<N, Name, S, T, Inner> {
+    pub(crate) inner: Inner,
+    pub(crate) name: Name,
+    pub(crate) source: S,
+    pub(crate) _p: PhantomData<(N, fn() -> T)>,
+}
+
+pub struct AttrBindViewState<InnerViewState> {
+    prev_value: Variant,
+    subscription: Option<Subscription>,
+    inner_view_state: InnerViewState,
+}
+
+fn bind<N, S, T>(mut node: Gd<N>, name: &str, source: &S) -> Subscription
+where
+    N: Inherits<Node>,
+    S: Reactive<T>,
+    T: ToGodot + 'static,
+{
+    let name = StringName::from(name);
+    // not a read by the views, the watch keeps the node up to date
+    let value = building(false, || source.read(|v| v.to_variant()));
+    node.upcast_mut().set(&name, &value);
+    let source_cb = source.clone();
+    source
+        .watch(move || {
+            if node.is_instance_valid() {
+                node.clone()
+                    .upcast_mut()
+                    .set(&name, &source_cb.read(|v| v.to_variant()));
+            }
+        })
+        .binding()
+}
+
+impl<N, Name, S, T, Inner> View for AttrBind<N, Name, S, T, Inner>
+where
+    Inner: ElementView<N>,
+    Name: AsRef<str> + Clone,
+    N: Inherits<Node>,
+    S: Reactive<T>,
+    T: ToGodot + 'static,
+{
+    type ViewState = AttrBindViewState<Inner::ViewState>;
+
+    fn build(
+        &self,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) -> Self::ViewState {
+        let inner_view_state = self.inner.build(ctx, anchor, anchor_type);
+        let node = self.inner.get_node(&inner_view_state);
+        let prev_value = node.upcast_ref().get(self.name.as_ref());
+        let subscription = bind(node, self.name.as_ref(), &self.source);
+        AttrBindViewState {
+            prev_value,
+            subscription: Some(subscription),
+            inner_view_state,
+        }
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) {
+        self.inner.rebuild(
+            &prev.inner,
+            &mut state.inner_view_state,
+            ctx,
+            anchor,
+            anchor_type,
+        );
+
+        let renamed = self.name.as_ref() != prev.name.as_ref();
+        if !renamed && self.source.same_source(&prev.source) {
+            return;
+        }
+        state.subscription = None;
+        let mut node = self.get_node(state);
+        if renamed {
+            node.upcast_mut().set(prev.name.as_ref(), &state.prev_value);
+            state.prev_value = node.upcast_ref().get(self.name.as_ref());
+        }
+        state.subscription = Some(bind(node, self.name.as_ref(), &self.source));
+    }
+
+    fn teardown(
+        &self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) {
+        state.subscription = None;
+        self.inner
+            .teardown(&mut state.inner_view_state, ctx, anchor, anchor_type);
+    }
+
+    fn notify_state(
+        &self,
+        path: &[crate::view::ViewId],
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::view::AnchorType,
+    ) {
+        self.inner
+            .notify_state(path, &mut state.inner_view_state, ctx, anchor, anchor_type);
+    }
+
+    fn collect_nodes(&self, state: &Self::ViewState, nodes: &mut Vec<Gd<Node>>) {
+        self.inner.collect_nodes(&state.inner_view_state, nodes);
+    }
+}
+
+impl<N, Name, S, T, Inner> ElementView<N> for AttrBind<N, Name, S, T, Inner>
+where
+    Inner: ElementView<N>,
+    Name: AsRef<str> + Clone,
+    N: Inherits<Node>,
+    S: Reactive<T>,
+    T: ToGodot + 'static,
+{
+    fn get_node(&self, state: &Self::ViewState) -> Gd<N> {
+        self.inner.get_node(&state.inner_view_state)
+    }
+}
+
+impl<N, Name0, S0, T0, Inner> AttrBind<N, Name0, S0, T0, Inner> {
+    impl_element_view! { N }
+}
@@ -0,0 +1,199 @@
+use godot::{
+    builtin::{Callable, StringName, Variant},
+    classes::Node,
+    meta::{FromGodot, ToGodot},
+    obj::Inherits,
+    prelude::Gd,
+};
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    rc::Rc,
+};
+
+use crate::{
+    State,
+    view::{
+        AnchorType, View,
+        element::{ElementView, impl_element_view},
+    },
+};
+
+/// Keeps a property and a `State` in sync both ways. The node writes to the state when
+/// `signal` is emitted, and the state writes to the node on rebuild.
+///
+/// Equal values are never written, so e.g. a `LineEdit` keeps its caret while typing.
+pub struct Bind<N, Name, Signal, T: 'static, Inner> {
+    pub(crate) inner: Inner,
+    pub(crate) name: Name,
+    pub(crate) signal: Signal,
+    pub(crate) state: State<T>,
+    pub(crate) _p: PhantomData<N>,
+}
+
+pub struct BindViewState<T: 'static, InnerViewState> {
+    link: Rc<Link<T>>,
+    callable: Callable,
+    prev_value: Variant,
+    inner_view_state: InnerViewState,
+}
+
+struct Link<T: 'static> {
+    state: Cell<State<T>>,
+    name: RefCell<StringName>,
+    /// Set while we write the property, so the signal it emits isn't written back.
+    writing: Cell<bool>,
+}
+
+impl<T: ToGodot + FromGodot + PartialEq> Link<T> {
+    fn read(&self, node: &Gd<Node>) -> Option<T> {
+        T::try_from_variant(&node.get(&*self.name.borrow())).ok()
+    }
+    /// State to node.
+    fn push(&self, mut node: Gd<Node>) {
+        let current = self.read(&node);
+        let Ok(value) = self
+            .state
+            .get()
+            .try_with(|v| (current.as_ref() != Some(v)).then(|| v.to_variant()))
+        else {
+            return;
+        };
+        if let Some(value) = value {
+            self.writing.set(true);
+            node.set(&*self.name.borrow(), &value);
+            self.writing.set(false);
+        }
+    }
+    /// Node to state.
+    fn pull(&self, node: &Gd<Node>) {
+        if self.writing.get() {
+            return;
+        }
+        let Some(value) = self.read(node) else {
+            return;
+        };
+        let state = self.state.get();
+        if state.try_with(|v| *v != value).unwrap_or(false) {
+            state.set(value);
+        }
+    }
+}
+
+impl<N, Name, Signal, T, Inner> View for Bind<N, Name, Signal, T, Inner>
+where
+    Inner: ElementView<N>,
+    Name: AsRef<str> + Clone,
+    Signal: AsRef<str> + Clone,
+    N: Inherits<Node>,
+    T: ToGodot + FromGodot + PartialEq + 'static,
+{
+    type ViewState = BindViewState<T, Inner::ViewState>;
+
+    fn build(
+        &self,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) -> Self::ViewState {
+        let inner_view_state = self.inner.build(ctx, anchor, anchor_type);
+        let mut node = self.inner.get_node(&inner_view_state).upcast::<Node>();
+        let prev_value = node.get(self.name.as_ref());
+
+        let link = Rc::new(Link {
+            state: Cell::new(self.state),
+            name: RefCell::new(StringName::from(self.name.as_ref())),
+            writing: Cell::new(false),
+        });
+        link.push(node.clone());
+
+        let (link_cb, node_cb) = (link.clone(), node.clone());
+        let callable = Callable::from_fn("lunar_bind", move |_| link_cb.pull(&node_cb));
+        node.connect(self.signal.as_ref(), &callable);
+
+        BindViewState {
+            link,
+            callable,
+            prev_value,
+            inner_view_state,
+        }
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) {
+        self.inner.rebuild(
+            &prev.inner,
+            &mut state.inner_view_state,
+            ctx,
+            anchor,
+            anchor_type,
+        );
+
+        let mut node = self.get_node(state).upcast::<Node>();
+        state.link.state.set(self.state);
+        if self.name.as_ref() != prev.name.as_ref() {
+            node.set(prev.name.as_ref(), &state.prev_value);
+            state.prev_value = node.get(self.name.as_ref());
+            *state.link.name.borrow_mut() = StringName::from(self.name.as_ref());
+        }
+        if self.signal.as_ref() != prev.signal.as_ref() {
+            node.disconnect(prev.signal.as_ref(), &state.callable);
+            node.connect(self.signal.as_ref(), &state.callable);
+        }
+        state.link.push(node);
+    }
+
+    fn teardown(
+        &self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) {
+        let mut node = self.get_node(state).upcast::<Node>();
+        if node.is_instance_valid() && node.is_connected(self.signal.as_ref(), &state.callable) {
+            node.disconnect(self.signal.as_ref(), &state.callable);
+        }
+        self.inner
+            .teardown(&mut state.inner_view_state, ctx, anchor, anchor_type);
+    }
+
+    fn notify_state(
+        &self,
+        path: &[crate::view::ViewId],
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::view::AnchorType,
+    ) {
+        self.inner
+            .notify_state(path, &mut state.inner_view_state, ctx, anchor, anchor_type);
+    }
+
+    fn collect_nodes(&self, state: &Self::ViewState, nodes: &mut Vec<Gd<Node>>) {
+        self.inner.collect_nodes(&state.inner_view_state, nodes);
+    }
+}
+
+impl<N, Name, Signal, T, Inner> ElementView<N> for Bind<N, Name, Signal, T, Inner>
+where
+    Inner: ElementView<N>,
+    Name: AsRef<str> + Clone,
+    Signal: AsRef<str> + Clone,
+    N: Inherits<Node>,
+    T: ToGodot + FromGodot + PartialEq + 'static,
+{
+    fn get_node(&self, state: &Self::ViewState) -> Gd<N> {
+        self.inner.get_node(&state.inner_view_state)
+    }
+}
+
+impl<N, Name0, Signal0, T0: 'static, Inner> Bind<N, Name0, Signal0, T0, Inner> {
+    impl_element_view! { N }
+}
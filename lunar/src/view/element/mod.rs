@@ -1,6 +1,12 @@
 pub mod attr;
+pub mod attr_bind;
+pub mod bind;
 pub mod node_ref;
 pub mod on_signal;
+pub mod on_typed;
+pub mod prop;
+pub mod props;
+pub mod signals;
 pub mod theme_override;
 
 use std::marker::PhantomData;
@@ -87,15 +93,13 @@ where
         anchor: &mut Node,
         anchor_type: AnchorType,
     ) {
-        self.children.teardown(
-            &mut state.child_view_state,
-            ctx,
-            state.node.upcast_mut(),
-            AnchorType::ChildOf,
-        );
+        let node = state.node.clone().upcast::<Node>();
+        with_anchor(&node, |node| {
+            self.children
+                .teardown(&mut state.child_view_state, ctx, node, AnchorType::ChildOf)
+        });
 
-        anchor_type.remove(anchor, &state.node.clone().upcast());
-        state.node.upcast_mut::<Node>().queue_free();
+        anchor_type.remove_and_free(anchor, node);
     }
 
     fn notify_state(
@@ -146,7 +150,7 @@ macro_rules! impl_element_view {
             $crate::view::element::attr::Attr {
                 inner: self,
                 name,
-                value: value.to_variant(),
+                value: Some(value.to_variant()),
                 _p: PhantomData,
             }
         }
@@ -164,7 +168,125 @@ macro_rules! impl_element_view {
             $crate::view::element::attr::Attr {
                 inner: self,
                 name,
-                value: value.to_variant(),
+                value: Some(value.to_variant()),
+                _p: PhantomData,
+            }
+        }
+        pub fn prop<V, Setter>(
+            self,
+            name: &'static str,
+            value: V,
+            setter: Setter,
+        ) -> $crate::view::element::prop::Prop<$node, V, Setter, Self, false>
+        where
+            V: godot::meta::ToGodot,
+            Setter: Fn(&mut godot::prelude::Gd<$node>, &V),
+            $node: godot::prelude::Inherits<godot::prelude::Node>,
+        {
+            use std::marker::PhantomData;
+            $crate::view::element::prop::Prop {
+                inner: self,
+                name,
+                value,
+                setter,
+                _p: PhantomData,
+            }
+        }
+        pub fn prop_build<V, Setter>(
+            self,
+            name: &'static str,
+            value: V,
+            setter: Setter,
+        ) -> $crate::view::element::prop::Prop<$node, V, Setter, Self, true>
+        where
+            V: godot::meta::ToGodot,
+            Setter: Fn(&mut godot::prelude::Gd<$node>, &V),
+            $node: godot::prelude::Inherits<godot::prelude::Node>,
+        {
+            use std::marker::PhantomData;
+            $crate::view::element::prop::Prop {
+                inner: self,
+                name,
+                value,
+                setter,
+                _p: PhantomData,
+            }
+        }
+        pub fn prop_opt<V, Setter>(
+            self,
+            name: &'static str,
+            value: Option<V>,
+            setter: Setter,
+        ) -> $crate::view::element::prop::PropOpt<$node, V, Setter, Self>
+        where
+            V: godot::meta::ToGodot,
+            Setter: Fn(&mut godot::prelude::Gd<$node>, &V),
+            $node: godot::prelude::Inherits<godot::prelude::Node>,
+        {
+            use std::marker::PhantomData;
+            $crate::view::element::prop::PropOpt {
+                inner: self,
+                name,
+                value,
+                setter,
+                _p: PhantomData,
+            }
+        }
+        pub fn attr_opt<Name, Value>(
+            self,
+            name: Name,
+            value: Option<Value>,
+        ) -> $crate::view::element::attr::Attr<$node, Name, Self, false>
+        where
+            Name: AsRef<str>,
+            Value: godot::meta::ToGodot,
+            $node: godot::prelude::Inherits<godot::prelude::Node>,
+        {
+            use std::marker::PhantomData;
+            $crate::view::element::attr::Attr {
+                inner: self,
+                name,
+                value: value.map(|v| v.to_variant()),
+                _p: PhantomData,
+            }
+        }
+        pub fn attr_bind<Name, S, T>(
+            self,
+            name: Name,
+            source: S,
+        ) -> $crate::view::element::attr_bind::AttrBind<$node, Name, S, T, Self>
+        where
+            Name: AsRef<str>,
+            S: $crate::Reactive<T>,
+            T: godot::meta::ToGodot + 'static,
+            $node: godot::prelude::Inherits<godot::prelude::Node>,
+        {
+            use std::marker::PhantomData;
+            $crate::view::element::attr_bind::AttrBind {
+                inner: self,
+                name,
+                source,
+                _p: PhantomData,
+            }
+        }
+        pub fn bind<Name, Signal, T>(
+            self,
+            name: Name,
+            signal: Signal,
+            state: $crate::State<T>,
+        ) -> $crate::view::element::bind::Bind<$node, Name, Signal, T, Self>
+        where
+            Name: AsRef<str>,
+            Signal: AsRef<str>,
+            T: godot::meta::ToGodot + godot::meta::FromGodot + PartialEq + 'static,
+            $node: godot::prelude::Inherits<godot::prelude::Node>,
+        {
+            use std::marker::PhantomData;
+            $crate::view::element::bind::Bind {
+                inner: self,
+                name,
+                signal,
+                state,
                 _p: PhantomData,
             }
         }
@@ -186,11 +308,49 @@ macro_rules! impl_element_view {
                 _p: PhantomData,
             }
         }
+        pub fn on_typed<Ps, Connect, Cb>(
+            self,
+            connect: Connect,
+            cb: Cb,
+        ) -> $crate::view::element::on_typed::OnTyped<$node, Ps, Connect, Cb, Self>
+        where
+            Connect: Fn(
+                &godot::prelude::Gd<$node>,
+                $crate::view::element::on_typed::TypedCallback<Ps>,
+            ) -> godot::register::ConnectHandle,
+            Cb: Fn(Ps) + 'static,
+            $node: godot::prelude::Inherits<godot::prelude::Node>,
+        {
+            use std::marker::PhantomData;
+            $crate::view::element::on_typed::OnTyped {
+                inner: self,
+                connect,
+                cb: cb.into(),
+                _p: PhantomData,
+            }
+        }
         pub fn theme_override<Typ: crate::ThemeOverrideType, Name>(
             self,
             name: Name,
             value: Typ::ValueType,
         ) -> $crate::ThemeOverride<$node, Typ, Name, Self>
+        where
+            Name: AsRef<str>,
+            $node: godot::prelude::Inherits<godot::prelude::Node>,
+        {
+            use std::marker::PhantomData;
+            $crate::ThemeOverride {
+                inner: self,
+                name,
+                value: Some(value),
+                _p: PhantomData,
+            }
+        }
+        pub fn theme_override_opt<Typ: crate::ThemeOverrideType, Name>(
+            self,
+            name: Name,
+            value: Option<Typ::ValueType>,
+        ) -> $crate::ThemeOverride<$node, Typ, Name, Self>
         where
             Name: AsRef<str>,
             $node: godot::prelude::Inherits<godot::prelude::Node>,
@@ -216,7 +376,7 @@ macro_rules! impl_element_view {
 }
 pub(crate) use impl_element_view;
 
-use crate::view::{AnchorType, View, ViewId};
+use crate::view::{AnchorType, View, ViewId, with_anchor};
 
 impl<N, Children> Element<N, Children> {
     impl_element_view! { N }
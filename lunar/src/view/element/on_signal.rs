@@ -5,7 +5,7 @@ use godot::{
     obj::Inherits,
     prelude::Gd,
 };
-use std::{marker::PhantomData, rc::Rc};
+use std::{cell::RefCell, marker::PhantomData, rc::Rc};
 
 use crate::view::{
     AnchorType, View,
@@ -19,7 +19,11 @@ pub struct OnSignal<N, Name, Cb, Inner> {
     pub(crate) _p: PhantomData<N>,
 }
 
+type CallbackCell = Rc<RefCell<Rc<dyn Fn(&[&Variant])>>>;
+
 pub struct OnSignalViewState<InnerViewState> {
+    /// What `callable` forwards to, swapped on rebuild so the connection can stay.
+    cb: CallbackCell,
     callable: Callable,
     inner_view_state: InnerViewState,
 }
@@ -42,11 +46,17 @@ where
         let inner_view_state = self.inner.build(ctx, anchor, anchor_type);
         let mut node = self.inner.get_node(&inner_view_state);
 
-        let cb = self.cb.clone();
-        let callable = Callable::from_fn("boing", move |args| cb(args));
+        let cb: CallbackCell = Rc::new(RefCell::new(self.cb.clone()));
+        let cell = cb.clone();
+        let callable = Callable::from_fn("boing", move |args| {
+            // the callback might get swapped while it runs
+            let cb = cell.borrow().clone();
+            cb(args)
+        });
 
         node.upcast_mut().connect(self.name.as_ref(), &callable);
         OnSignalViewState {
+            cb,
             callable,
             inner_view_state,
         }
@@ -67,16 +77,14 @@ where
             anchor,
             anchor_type,
         );
-        let mut node = self.get_node(state);
-
-        node.upcast_mut()
-            .disconnect(prev.name.as_ref(), &state.callable);
-
-        let cb = self.cb.clone();
-        let callable = Callable::from_fn("boing", move |args| cb(args));
-
-        node.upcast_mut().connect(self.name.as_ref(), &callable);
-        state.callable = callable;
+        *state.cb.borrow_mut() = self.cb.clone();
+        if self.name.as_ref() != prev.name.as_ref() {
+            let mut node = self.get_node(state);
+            node.upcast_mut()
+                .disconnect(prev.name.as_ref(), &state.callable);
+            node.upcast_mut()
+                .connect(self.name.as_ref(), &state.callable);
+        }
     }
 
     fn teardown(
@@ -86,6 +94,10 @@ where
         anchor: &mut Node,
         anchor_type: AnchorType,
     ) {
+        let mut node = self.get_node(state).upcast::<Node>();
+        if node.is_instance_valid() && node.is_connected(self.name.as_ref(), &state.callable) {
+            node.disconnect(self.name.as_ref(), &state.callable);
+        }
         self.inner
             .teardown(&mut state.inner_view_state, ctx, anchor, anchor_type);
     }
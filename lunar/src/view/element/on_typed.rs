@@ -0,0 +1,138 @@
+use godot::{classes::Node, obj::Inherits, prelude::Gd, register::ConnectHandle};
+use std::{cell::RefCell, marker::PhantomData, rc::Rc};
+
+use crate::view::{
+    AnchorType, View,
+    element::{ElementView, impl_element_view},
+};
+
+type CallbackCell<Ps> = Rc<RefCell<Rc<dyn Fn(Ps)>>>;
+
+/// Connects to a signal through gdext's typed signals, so the callback gets decoded
+/// arguments and a wrong signal name or arity doesn't compile.
+///
+/// `connect` makes the connection once, e.g.
+/// `|node, cb| node.signals().text_changed().connect(move |t| cb.call((t,)))`,
+/// and later rebuilds only swap the callback it forwards to. The `on_<signal>` methods in
+/// `lunar::signals` make one of these for every engine signal.
+pub struct OnTyped<N, Ps, Connect, Cb, Inner> {
+    pub(crate) inner: Inner,
+    pub(crate) connect: Connect,
+    pub(crate) cb: Rc<Cb>,
+    pub(crate) _p: PhantomData<(N, fn(Ps))>,
+}
+
+pub struct OnTypedViewState<Ps, InnerViewState> {
+    cell: CallbackCell<Ps>,
+    handle: Option<ConnectHandle>,
+    inner_view_state: InnerViewState,
+}
+
+/// The callback as seen from the connection made by `OnTyped`.
+pub struct TypedCallback<Ps> {
+    cell: CallbackCell<Ps>,
+}
+impl<Ps> TypedCallback<Ps> {
+    pub fn call(&self, args: Ps) {
+        // the callback might get swapped while it runs
+        let cb = self.cell.borrow().clone();
+        cb(args)
+    }
+}
+
+impl<N, Ps, Connect, Cb, Inner> View for OnTyped<N, Ps, Connect, Cb, Inner>
+where
+    Inner: ElementView<N>,
+    Connect: Fn(&Gd<N>, TypedCallback<Ps>) -> ConnectHandle,
+    Cb: Fn(Ps) + 'static,
+    Ps: 'static,
+    N: Inherits<Node>,
+{
+    type ViewState = OnTypedViewState<Ps, Inner::ViewState>;
+
+    fn build(
+        &self,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) -> Self::ViewState {
+        let inner_view_state = self.inner.build(ctx, anchor, anchor_type);
+        let node = self.inner.get_node(&inner_view_state);
+
+        let cell = Rc::new(RefCell::new(self.cb.clone() as Rc<dyn Fn(Ps)>));
+        let handle = (self.connect)(&node, TypedCallback { cell: cell.clone() });
+        OnTypedViewState {
+            cell,
+            handle: Some(handle),
+            inner_view_state,
+        }
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) {
+        self.inner.rebuild(
+            &prev.inner,
+            &mut state.inner_view_state,
+            ctx,
+            anchor,
+            anchor_type,
+        );
+        *state.cell.borrow_mut() = self.cb.clone();
+    }
+
+    fn teardown(
+        &self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) {
+        if let Some(handle) = state.handle.take()
+            && self.get_node(state).is_instance_valid()
+            && handle.is_connected()
+        {
+            handle.disconnect();
+        }
+        self.inner
+            .teardown(&mut state.inner_view_state, ctx, anchor, anchor_type);
+    }
+
+    fn notify_state(
+        &self,
+        path: &[crate::view::ViewId],
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::view::AnchorType,
+    ) {
+        self.inner
+            .notify_state(path, &mut state.inner_view_state, ctx, anchor, anchor_type);
+    }
+
+    fn collect_nodes(&self, state: &Self::ViewState, nodes: &mut Vec<Gd<Node>>) {
+        self.inner.collect_nodes(&state.inner_view_state, nodes);
+    }
+}
+
+impl<N, Ps, Connect, Cb, Inner> ElementView<N> for OnTyped<N, Ps, Connect, Cb, Inner>
+where
+    Inner: ElementView<N>,
+    Connect: Fn(&Gd<N>, TypedCallback<Ps>) -> ConnectHandle,
+    Cb: Fn(Ps) + 'static,
+    Ps: 'static,
+    N: Inherits<Node>,
+{
+    fn get_node(&self, state: &Self::ViewState) -> Gd<N> {
+        self.inner.get_node(&state.inner_view_state)
+    }
+}
+
+impl<N, Ps0, Connect0, Cb0, Inner> OnTyped<N, Ps0, Connect0, Cb0, Inner> {
+    impl_element_view! { N }
+}
@@ -0,0 +1,310 @@
+use godot::{
+    builtin::{StringName, Variant},
+    classes::Node,
+    meta::ToGodot,
+    obj::Inherits,
+    prelude::Gd,
+};
+use std::marker::PhantomData;
+
+use crate::view::{
+    AnchorType, View,
+    element::{ElementView, attr::restore, impl_element_view},
+};
+
+/// Like `Attr`, but sets the value through a typed setter such as `Label::set_text`, so
+/// wrong names and value types are caught at compile time.
+///
+/// Like `Attr`, rebuilds only write when the node's current value differs, so a control
+/// the user changed is put back to the declared value.
+pub struct Prop<N, V, Setter, Inner, const BUILD_ONLY: bool> {
+    pub(crate) inner: Inner,
+    /// The property the setter writes, to read the node's current value.
+    pub(crate) name: &'static str,
+    pub(crate) value: V,
+    pub(crate) setter: Setter,
+    pub(crate) _p: PhantomData<N>,
+}
+
+pub struct PropViewState<InnerViewState> {
+    name: StringName,
+    inner_view_state: InnerViewState,
+}
+
+impl<N, V, Setter, Inner, const BUILD_ONLY: bool> View for Prop<N, V, Setter, Inner, BUILD_ONLY>
+where
+    Inner: ElementView<N>,
+    V: ToGodot,
+    Setter: Fn(&mut Gd<N>, &V),
+    N: Inherits<Node>,
+{
+    type ViewState = PropViewState<Inner::ViewState>;
+
+    fn build(
+        &self,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) -> Self::ViewState {
+        let inner_view_state = self.inner.build(ctx, anchor, anchor_type);
+        let mut node = self.inner.get_node(&inner_view_state);
+        (self.setter)(&mut node, &self.value);
+        PropViewState {
+            name: StringName::from(self.name),
+            inner_view_state,
+        }
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) {
+        self.inner.rebuild(
+            &prev.inner,
+            &mut state.inner_view_state,
+            ctx,
+            anchor,
+            anchor_type,
+        );
+
+        if !BUILD_ONLY {
+            if self.name != prev.name {
+                state.name = StringName::from(self.name);
+            }
+            let mut node = self.get_node(state);
+            if node.upcast_ref().get(&state.name) != self.value.to_variant() {
+                (self.setter)(&mut node, &self.value);
+            }
+        }
+    }
+
+    fn teardown(
+        &self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) {
+        self.inner
+            .teardown(&mut state.inner_view_state, ctx, anchor, anchor_type);
+    }
+
+    fn notify_state(
+        &self,
+        path: &[crate::view::ViewId],
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::view::AnchorType,
+    ) {
+        self.inner
+            .notify_state(path, &mut state.inner_view_state, ctx, anchor, anchor_type);
+    }
+
+    fn collect_nodes(&self, state: &Self::ViewState, nodes: &mut Vec<Gd<Node>>) {
+        self.inner.collect_nodes(&state.inner_view_state, nodes);
+    }
+}
+
+impl<N, V, Setter, Inner, const BUILD_ONLY: bool> ElementView<N>
+    for Prop<N, V, Setter, Inner, BUILD_ONLY>
+where
+    Inner: ElementView<N>,
+    V: ToGodot,
+    Setter: Fn(&mut Gd<N>, &V),
+    N: Inherits<Node>,
+{
+    fn get_node(&self, state: &Self::ViewState) -> Gd<N> {
+        self.inner.get_node(&state.inner_view_state)
+    }
+}
+
+impl<N, V0, Setter0, Inner, const BUILD_ONLY0: bool> Prop<N, V0, Setter0, Inner, BUILD_ONLY0> {
+    impl_element_view! { N }
+}
+
+/// `Prop` for an optional value: `None` puts back the value from before Lunar, like
+/// `attr_opt`.
+pub struct PropOpt<N, V, Setter, Inner> {
+    pub(crate) inner: Inner,
+    pub(crate) name: &'static str,
+    pub(crate) value: Option<V>,
+    pub(crate) setter: Setter,
+    pub(crate) _p: PhantomData<N>,
+}
+
+pub struct PropOptViewState<InnerViewState> {
+    name: StringName,
+    /// The value from before Lunar touched the property.
+    prev_value: Variant,
+    inner_view_state: InnerViewState,
+}
+
+impl<N, V, Setter, Inner> View for PropOpt<N, V, Setter, Inner>
+where
+    Inner: ElementView<N>,
+    V: ToGodot,
+    Setter: Fn(&mut Gd<N>, &V),
+    N: Inherits<Node>,
+{
+    type ViewState = PropOptViewState<Inner::ViewState>;
+
+    fn build(
+        &self,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) -> Self::ViewState {
+        let inner_view_state = self.inner.build(ctx, anchor, anchor_type);
+        let mut node = self.inner.get_node(&inner_view_state);
+        let name = StringName::from(self.name);
+        let prev_value = node.upcast_ref().get(&name);
+        if let Some(value) = &self.value {
+            (self.setter)(&mut node, value);
+        }
+        PropOptViewState {
+            name,
+            prev_value,
+            inner_view_state,
+        }
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) {
+        self.inner.rebuild(
+            &prev.inner,
+            &mut state.inner_view_state,
+            ctx,
+            anchor,
+            anchor_type,
+        );
+
+        let renamed = self.name != prev.name;
+        let mut node = self.get_node(state);
+        if renamed {
+            if prev.value.is_some() {
+                restore(&mut node.clone().upcast(), &state.name, &state.prev_value);
+            }
+            state.name = StringName::from(self.name);
+            state.prev_value = node.upcast_ref().get(&state.name);
+        }
+        match &self.value {
+            Some(value) if node.upcast_ref().get(&state.name) != value.to_variant() => {
+                (self.setter)(&mut node, value)
+            }
+            Some(_) => {}
+            None if !renamed && prev.value.is_some() => {
+                restore(&mut node.upcast(), &state.name, &state.prev_value)
+            }
+            None => {}
+        }
+    }
+
+    fn teardown(
+        &self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut Node,
+        anchor_type: AnchorType,
+    ) {
+        let mut node = self.get_node(state).upcast::<Node>();
+        if self.value.is_some() && node.is_instance_valid() {
+            restore(&mut node, &state.name, &state.prev_value);
+        }
+        self.inner
+            .teardown(&mut state.inner_view_state, ctx, anchor, anchor_type);
+    }
+
+    fn notify_state(
+        &self,
+        path: &[crate::view::ViewId],
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::view::AnchorType,
+    ) {
+        self.inner
+            .notify_state(path, &mut state.inner_view_state, ctx, anchor, anchor_type);
+    }
+
+    fn collect_nodes(&self, state: &Self::ViewState, nodes: &mut Vec<Gd<Node>>) {
+        self.inner.collect_nodes(&state.inner_view_state, nodes);
+    }
+}
+
+impl<N, V, Setter, Inner> ElementView<N> for PropOpt<N, V, Setter, Inner>
+where
+    Inner: ElementView<N>,
+    V: ToGodot,
+    Setter: Fn(&mut Gd<N>, &V),
+    N: Inherits<Node>,
+{
+    fn get_node(&self, state: &Self::ViewState) -> Gd<N> {
+        self.inner.get_node(&state.inner_view_state)
+    }
+}
+
+impl<N, V0, Setter0, Inner> PropOpt<N, V0, Setter0, Inner> {
+    impl_element_view! { N }
+}
+
+/// Turns the `&V` a setter gets into whatever godot's `impl AsArg<_>` wants, used by
+/// `view!` as `(&&&PropArg(v)).prop_arg()`. The impls are picked by how many `&` they
+/// need, so the ones for specific types win over the general ones.
+pub mod arg {
+    use godot::{
+        meta::{ByValue, ToGodot},
+        obj::{Gd, GodotClass},
+    };
+
+    pub struct PropArg<'a, V>(pub &'a V);
+
+    pub trait ArgSpecific<'a> {
+        type Arg;
+        fn prop_arg(&self) -> Self::Arg;
+    }
+    impl<'a> ArgSpecific<'a> for &&PropArg<'a, String> {
+        type Arg = &'a String;
+        fn prop_arg(&self) -> Self::Arg {
+            self.0
+        }
+    }
+    impl<'a, T: GodotClass> ArgSpecific<'a> for &&PropArg<'a, Option<Gd<T>>> {
+        type Arg = Option<&'a Gd<T>>;
+        fn prop_arg(&self) -> Self::Arg {
+            self.0.as_ref()
+        }
+    }
+
+    pub trait ArgByValue<'a> {
+        type Arg;
+        fn prop_arg(&self) -> Self::Arg;
+    }
+    impl<'a, T: ToGodot<Pass = ByValue> + Clone> ArgByValue<'a> for &PropArg<'a, T> {
+        type Arg = T;
+        fn prop_arg(&self) -> Self::Arg {
+            self.0.clone()
+        }
+    }
+
+    pub trait ArgByRef<'a> {
+        type Arg;
+        fn prop_arg(&self) -> Self::Arg;
+    }
+    impl<'a, T: ToGodot> ArgByRef<'a> for PropArg<'a, T> {
+        type Arg = &'a T;
+        fn prop_arg(&self) -> Self::Arg {
+            self.0
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! Typed builder methods for godot properties, one trait per class, generated from the same
+//! extension API metadata gdext uses. Bring them in with `use lunar::props::*`, e.g.
+//! `el::<Label>().text("hi")`.
+
+use godot::{
+    classes::Node,
+    obj::{Gd, Inherits},
+};
+use std::marker::PhantomData;
+
+use crate::view::element::{ElementView, prop::Prop};
+
+lunar_macro::__element_props!();
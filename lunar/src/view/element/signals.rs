@@ -0,0 +1,17 @@
+//! Typed handlers for godot signals, one trait per class, generated from the same
+//! extension API metadata gdext uses. Bring them in with `use lunar::signals::*`, e.g.
+//! `el::<LineEdit>().on_text_changed(|text| ..)`.
+
+use godot::{
+    classes::Node,
+    obj::{Gd, Inherits},
+    register::ConnectHandle,
+};
+use std::{marker::PhantomData, rc::Rc};
+
+use crate::view::element::{
+    ElementView,
+    on_typed::{OnTyped, TypedCallback},
+};
+
+lunar_macro::__element_signals!();
@@ -29,6 +29,13 @@ macro_rules! theme_override_types {
                     fn remove(node: &mut Control, name: impl AsArg<StringName>) {
                         node.[< remove_theme_ $name _override >](name);
                     }
+                    fn get_override(node: &Control, name: &StringName) -> Option<Self::ValueType> {
+                        if node.[< has_theme_ $name _override >](name) {
+                            Option::from(node.[< get_theme_ $name >](name))
+                        } else {
+                            None
+                        }
+                    }
                 }
             )*
         }
@@ -38,6 +45,8 @@ pub trait ThemeOverrideType: Copy {
     type ValueType: Clone + PartialEq;
     fn set(node: &mut Control, name: impl AsArg<StringName>, value: Self::ValueType);
     fn remove(node: &mut Control, name: impl AsArg<StringName>);
+    /// The override the node has, if any.
+    fn get_override(node: &Control, name: &StringName) -> Option<Self::ValueType>;
 }
 theme_override_types! {
     color: Color,
@@ -51,14 +60,29 @@ theme_override_types! {
 pub struct ThemeOverride<N, Typ: ThemeOverrideType, Name, Inner> {
     pub(crate) inner: Inner,
     pub(crate) name: Name,
-    pub(crate) value: Typ::ValueType,
+    /// `None` leaves the override as it was before Lunar set it.
+    pub(crate) value: Option<Typ::ValueType>,
     pub(crate) _p: PhantomData<(N, Typ)>,
 }
 
-pub struct ThemeOverrideViewState<InnerViewState> {
+pub struct ThemeOverrideViewState<Typ: ThemeOverrideType, InnerViewState> {
+    name: StringName,
+    /// The override from before Lunar touched it.
+    prev_value: Option<Typ::ValueType>,
     inner_view_state: InnerViewState,
 }
 
+fn restore<Typ: ThemeOverrideType>(
+    node: &mut Control,
+    name: &StringName,
+    prev_value: &Option<Typ::ValueType>,
+) {
+    match prev_value {
+        Some(value) => Typ::set(node, name, value.clone()),
+        None => Typ::remove(node, name),
+    }
+}
+
 impl<N, Typ, Name, Inner> View for ThemeOverride<N, Typ, Name, Inner>
 where
     Inner: ElementView<N>,
@@ -66,7 +90,7 @@ where
     Name: AsRef<str> + Clone,
     N: Inherits<Control> + Inherits<Node>,
 {
-    type ViewState = ThemeOverrideViewState<Inner::ViewState>;
+    type ViewState = ThemeOverrideViewState<Typ, Inner::ViewState>;
 
     fn build(
         &self,
@@ -76,8 +100,16 @@ where
     ) -> Self::ViewState {
         let inner_view_state = self.inner.build(ctx, anchor, anchor_type);
         let mut node = self.inner.get_node(&inner_view_state);
-        Typ::set(node.upcast_mut(), self.name.as_ref(), self.value.clone());
-        ThemeOverrideViewState { inner_view_state }
+        let name = StringName::from(self.name.as_ref());
+        let prev_value = Typ::get_override(node.upcast_ref(), &name);
+        if let Some(value) = &self.value {
+            Typ::set(node.upcast_mut(), &name, value.clone());
+        }
+        ThemeOverrideViewState {
+            name,
+            prev_value,
+            inner_view_state,
+        }
     }
 
     fn rebuild(
@@ -96,11 +128,29 @@ where
             anchor_type,
         );
 
+        let renamed = self.name.as_ref() != prev.name.as_ref();
         let mut node = self.get_node(state);
-        if self.name.as_ref() != prev.name.as_ref() {
-            Typ::remove(node.upcast_mut(), prev.name.as_ref());
+        if renamed {
+            if prev.value.is_some() {
+                restore::<Typ>(node.upcast_mut(), &state.name, &state.prev_value);
+            }
+            state.name = StringName::from(self.name.as_ref());
+            state.prev_value = Typ::get_override(node.upcast_ref(), &state.name);
+        }
+        match &self.value {
+            // compared with the node like `Attr`
+            Some(value)
+                if Typ::get_override(node.upcast_ref(), &state.name).as_ref() != Some(value) =>
+            {
+                Typ::set(node.upcast_mut(), &state.name, value.clone())
+            }
+            Some(_) => {}
+            // the value was just taken away
+            None if !renamed && prev.value.is_some() => {
+                restore::<Typ>(node.upcast_mut(), &state.name, &state.prev_value)
+            }
+            None => {}
         }
-        Typ::set(node.upcast_mut(), self.name.as_ref(), self.value.clone());
     }
 
     fn teardown(
@@ -110,6 +160,10 @@ where
         anchor: &mut Node,
         anchor_type: AnchorType,
     ) {
+        let mut node = self.get_node(state);
+        if self.value.is_some() && node.is_instance_valid() {
+            restore::<Typ>(node.upcast_mut(), &state.name, &state.prev_value);
+        }
         self.inner
             .teardown(&mut state.inner_view_state, ctx, anchor, anchor_type);
     }
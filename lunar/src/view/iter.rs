@@ -4,7 +4,7 @@ use godot::{
 };
 use std::{cell::Cell, collections::HashMap, hash::Hash};
 
-use crate::{AnchorType, View, ViewId, util::hash};
+use crate::{AnchorType, View, ViewId, util::hash, view::with_anchor};
 
 pub struct VecViewState<InnerViewState> {
     anchor: Gd<Node>,
@@ -122,15 +122,14 @@ where
             state.inner.len(),
             "Bruh why are they not the same"
         );
-        let mut vec_anchor = state.anchor.clone();
-
-        for ((k, inner), state) in self.iter().zip(&mut state.inner) {
-            ctx.with_id(ViewId::Key(hash(k)), |ctx| {
-                inner.teardown(state, ctx, &mut vec_anchor, AnchorType::Before);
-            });
-        }
-        anchor_type.remove(anchor, &vec_anchor);
-        vec_anchor.queue_free();
+        with_anchor(&state.anchor, |vec_anchor| {
+            for ((k, inner), state) in self.iter().zip(&mut state.inner) {
+                ctx.with_id(ViewId::Key(hash(k)), |ctx| {
+                    inner.teardown(state, ctx, vec_anchor, AnchorType::Before);
+                });
+            }
+        });
+        anchor_type.remove_and_free(anchor, state.anchor.clone());
     }
 
     fn notify_state(
@@ -1,4 +1,5 @@
 pub mod any;
+pub mod computed;
 pub mod either;
 pub mod element;
 pub mod iter;
@@ -11,12 +12,18 @@ pub mod on_ready;
 pub mod on_rebuild;
 pub mod on_teardown;
 pub mod option;
+pub mod provide;
 pub mod stateful;
+pub mod store;
+pub mod task;
 pub mod when;
 
 use std::{ops::Deref, rc::Rc};
 
-use godot::{classes::Node, obj::Gd};
+use godot::{
+    classes::Node,
+    obj::{Gd, NewAlloc},
+};
 
 use crate::ctx::Context;
 
@@ -44,11 +51,35 @@ impl AnchorType {
         }
     }
     pub fn remove(self, anchor: &mut Node, node: &Gd<Node>) {
+        // freed along with an ancestor, e.g. the app's root
+        if !node.is_instance_valid() {
+            return;
+        }
         match self {
             AnchorType::ChildOf => anchor.remove_child(node),
             AnchorType::Before => anchor.get_parent().unwrap().remove_child(node),
         }
     }
+    /// Removes `node` and frees it, unless it's already gone.
+    pub(crate) fn remove_and_free(self, anchor: &mut Node, mut node: Gd<Node>) {
+        if node.is_instance_valid() {
+            self.remove(anchor, &node);
+            node.queue_free();
+        }
+    }
+}
+
+/// Runs `f` with `node` as the anchor, or with a stand-in if it was already freed, so a
+/// teardown still does everything that isn't about nodes.
+pub(crate) fn with_anchor<R>(node: &Gd<Node>, f: impl FnOnce(&mut Node) -> R) -> R {
+    if node.is_instance_valid() {
+        f(&mut node.clone())
+    } else {
+        let mut stand_in = Node::new_alloc();
+        let ret = f(&mut stand_in);
+        stand_in.free();
+        ret
+    }
 }
 
 pub trait View {
@@ -65,8 +65,7 @@ where
         anchor: &mut godot::prelude::Node,
         anchor_type: super::AnchorType,
     ) {
-        anchor_type.remove(anchor, &state.clone().upcast());
-        state.upcast_mut::<Node>().queue_free();
+        anchor_type.remove_and_free(anchor, state.clone().upcast());
     }
 
     fn notify_state(
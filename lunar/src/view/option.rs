@@ -7,7 +7,7 @@ use godot::{
 
 use crate::{
     ctx::Context,
-    view::{AnchorType, View, ViewId},
+    view::{AnchorType, View, ViewId, with_anchor},
 };
 
 pub struct OptionViewState<InnerViewState> {
@@ -101,15 +101,14 @@ where
             state.inner.is_some(),
             "Bruh why are they not the same"
         );
-        let mut opt_anchor = state.anchor.clone();
-
         if let Some((val, (inner, id))) = self.as_ref().zip(state.inner.as_mut()) {
             ctx.with_id(*id, |ctx| {
-                val.teardown(inner, ctx, &mut opt_anchor, AnchorType::Before);
+                with_anchor(&state.anchor, |opt_anchor| {
+                    val.teardown(inner, ctx, opt_anchor, AnchorType::Before)
+                });
             });
         }
-        anchor_type.remove(anchor, &opt_anchor);
-        opt_anchor.queue_free();
+        anchor_type.remove_and_free(anchor, state.anchor.clone());
     }
 
     fn notify_state(
@@ -0,0 +1,136 @@
+use std::{
+    any::{Any, TypeId},
+    rc::Rc,
+};
+
+use crate::{View, system::PROVIDERS};
+
+pub struct Provide<T, InnerFn> {
+    value: T,
+    inner_fn: InnerFn,
+}
+
+pub struct ProvideViewState<Inner: View> {
+    inner: Inner,
+    inner_state: Inner::ViewState,
+}
+
+impl<T: Clone + 'static, InnerFn> Provide<T, InnerFn> {
+    fn with_provided<R>(&self, f: impl FnOnce() -> R) -> R {
+        PROVIDERS.with_borrow_mut(|providers| {
+            providers.push((TypeId::of::<T>(), Rc::new(self.value.clone())))
+        });
+        let out = f();
+        PROVIDERS.with_borrow_mut(|providers| providers.pop());
+        out
+    }
+}
+
+impl<T, InnerFn, Inner> View for Provide<T, InnerFn>
+where
+    T: Clone + 'static,
+    InnerFn: Fn() -> Inner,
+    Inner: View,
+{
+    type ViewState = ProvideViewState<Inner>;
+
+    fn build(
+        &self,
+        ctx: &mut crate::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) -> Self::ViewState {
+        self.with_provided(|| {
+            let inner = (self.inner_fn)();
+            let inner_state = inner.build(ctx, anchor, anchor_type);
+            ProvideViewState { inner, inner_state }
+        })
+    }
+
+    fn rebuild(
+        &self,
+        _prev: &Self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) {
+        self.with_provided(|| {
+            let inner = (self.inner_fn)();
+            inner.rebuild(
+                &state.inner,
+                &mut state.inner_state,
+                ctx,
+                anchor,
+                anchor_type,
+            );
+            state.inner = inner;
+        });
+    }
+
+    fn teardown(
+        &self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) {
+        self.with_provided(|| {
+            state
+                .inner
+                .teardown(&mut state.inner_state, ctx, anchor, anchor_type);
+        });
+    }
+
+    fn notify_state(
+        &self,
+        path: &[super::ViewId],
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) {
+        // descendants rebuilt from here still need to see the value
+        self.with_provided(|| {
+            state
+                .inner
+                .notify_state(path, &mut state.inner_state, ctx, anchor, anchor_type);
+        });
+    }
+
+    fn collect_nodes(
+        &self,
+        state: &Self::ViewState,
+        nodes: &mut Vec<godot::prelude::Gd<godot::prelude::Node>>,
+    ) {
+        state.inner.collect_nodes(&state.inner_state, nodes);
+    }
+}
+
+/// Makes `value` available to `use_context` anywhere in the views built by `inner_fn`.
+///
+/// Provide a `State` to let consumers rebuild when it changes.
+pub fn provide<T, InnerFn, Inner>(value: T, inner_fn: InnerFn) -> Provide<T, InnerFn>
+where
+    T: Clone + 'static,
+    InnerFn: Fn() -> Inner,
+    Inner: View,
+{
+    Provide { value, inner_fn }
+}
+
+/// Gets the value of the closest `provide` of type `T` above the view being built.
+///
+/// Only works while views are being built, so call it inside a `stateful`, `when`, `memo`
+/// or `provide` closure rather than when creating the view tree.
+pub fn use_context<T: Clone + 'static>() -> Option<T> {
+    let value = PROVIDERS.with_borrow(|providers| {
+        providers
+            .iter()
+            .rev()
+            .find(|(id, _)| *id == TypeId::of::<T>())
+            .map(|(_, v)| v.clone())
+    })?;
+    let value: &dyn Any = &*value;
+    value.downcast_ref::<T>().cloned()
+}
@@ -0,0 +1,226 @@
+use std::collections::VecDeque;
+
+use crate::{
+    State, StateError, View,
+    view::stateful::{
+        Stateful,
+        state::{state_data, unwrap_state},
+        stateful,
+    },
+};
+
+const DEFAULT_CAPACITY: usize = 100;
+
+pub struct History<T> {
+    past: VecDeque<T>,
+    present: T,
+    future: Vec<T>,
+    capacity: usize,
+    merging: bool,
+    merged: bool,
+}
+
+impl<T: Clone> History<T> {
+    fn new(present: T) -> Self {
+        Self {
+            past: VecDeque::new(),
+            present,
+            future: vec![],
+            capacity: DEFAULT_CAPACITY,
+            merging: false,
+            merged: false,
+        }
+    }
+    /// Saves the current value before it gets overwritten.
+    fn record(&mut self) {
+        if self.merging {
+            if self.merged {
+                return;
+            }
+            self.merged = true;
+        }
+        self.past.push_back(self.present.clone());
+        self.future.clear();
+        self.trim();
+    }
+    fn trim(&mut self) {
+        while self.past.len() > self.capacity {
+            self.past.pop_front();
+        }
+    }
+}
+
+/// A state that remembers its previous values, made with `stateful_history`.
+pub struct HistoryState<T: 'static> {
+    pub(crate) state: State<History<T>>,
+}
+impl<T> Copy for HistoryState<T> {}
+impl<T> Clone for HistoryState<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Clone> HistoryState<T> {
+    pub fn is_valid(&self) -> bool {
+        self.state.is_valid()
+    }
+    pub fn try_with<R, F>(&self, f: F) -> Result<R, StateError>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.state.try_with(|h| f(&h.present))
+    }
+    pub fn try_get(&self) -> Result<T, StateError>
+    where
+        T: Copy,
+    {
+        self.try_with(|v| *v)
+    }
+    pub fn try_get_clone(&self) -> Result<T, StateError> {
+        self.try_with(|v| v.clone())
+    }
+    pub fn try_set(&self, to: T) -> Result<(), StateError> {
+        self.try_update(|v| *v = to)
+    }
+    pub fn try_update<R, F>(&self, f: F) -> Result<R, StateError>
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        self.state.try_update(|h| {
+            h.record();
+            f(&mut h.present)
+        })
+    }
+    #[track_caller]
+    pub fn get(&self) -> T
+    where
+        T: Copy,
+    {
+        self.state.with(|h| h.present)
+    }
+    #[track_caller]
+    pub fn get_clone(&self) -> T {
+        self.state.with(|h| h.present.clone())
+    }
+    #[track_caller]
+    pub fn with<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.state.with(|h| f(&h.present))
+    }
+    #[track_caller]
+    pub fn set(&self, to: T) {
+        self.state.update(|h| {
+            h.record();
+            h.present = to;
+        });
+    }
+    #[track_caller]
+    pub fn update<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        self.state.update(|h| {
+            h.record();
+            f(&mut h.present)
+        })
+    }
+    /// Goes back to the previous value, returns whether there was one.
+    #[track_caller]
+    pub fn undo(&self) -> bool {
+        if !self.can_undo() {
+            return false;
+        }
+        self.state.update(|h| {
+            let prev = h.past.pop_back().unwrap();
+            let present = std::mem::replace(&mut h.present, prev);
+            h.future.push(present);
+            h.merged = false;
+        });
+        true
+    }
+    /// Reapplies the last undone value, returns whether there was one.
+    #[track_caller]
+    pub fn redo(&self) -> bool {
+        if !self.can_redo() {
+            return false;
+        }
+        self.state.update(|h| {
+            let next = h.future.pop().unwrap();
+            let present = std::mem::replace(&mut h.present, next);
+            h.past.push_back(present);
+            h.merged = false;
+        });
+        true
+    }
+    #[track_caller]
+    pub fn can_undo(&self) -> bool {
+        self.state.with(|h| !h.past.is_empty())
+    }
+    #[track_caller]
+    pub fn can_redo(&self) -> bool {
+        self.state.with(|h| !h.future.is_empty())
+    }
+    /// Changes the bookkeeping without touching the value, so without notifying.
+    #[track_caller]
+    fn edit(&self, f: impl FnOnce(&mut History<T>)) {
+        let State {
+            state_id,
+            app_id,
+            created,
+            ..
+        } = self.state;
+        let result = state_data(state_id, app_id).and_then(|(value, _)| {
+            let mut value = value.try_borrow_mut().map_err(|_| StateError::Borrowed)?;
+            f(value.downcast_mut().ok_or(StateError::WrongType)?);
+            Ok(())
+        });
+        unwrap_state(state_id, created, result)
+    }
+    /// Sets how many undo steps are kept, the oldest ones are dropped first.
+    #[track_caller]
+    pub fn set_capacity(&self, capacity: usize) {
+        self.edit(|h| {
+            h.capacity = capacity;
+            h.trim();
+        });
+    }
+    /// Until `end_merge`, all writes are undone as a single step, e.g. for a slider drag.
+    #[track_caller]
+    pub fn begin_merge(&self) {
+        self.edit(|h| {
+            h.merging = true;
+            h.merged = false;
+        });
+    }
+    #[track_caller]
+    pub fn end_merge(&self) {
+        self.edit(|h| h.merging = false);
+    }
+    #[track_caller]
+    pub fn clear_history(&self) {
+        self.state.update(|h| {
+            h.past.clear();
+            h.future.clear();
+        });
+    }
+}
+
+#[track_caller]
+pub fn stateful_history<T, Inner, StateFn, InnerFn>(
+    init: StateFn,
+    view: InnerFn,
+) -> Stateful<impl FnOnce() -> History<T>, impl Fn(State<History<T>>) -> Inner, false>
+where
+    T: Clone + 'static,
+    StateFn: FnOnce() -> T,
+    InnerFn: Fn(HistoryState<T>) -> Inner,
+    Inner: View,
+{
+    stateful(
+        move || History::new(init()),
+        move |state| view(HistoryState { state }),
+    )
+}
@@ -1,4 +1,9 @@
+pub mod history;
+pub mod persisted;
+pub mod reducer;
+pub mod sender;
 pub mod state;
+pub mod sub_state;
 
 use std::{
     cell::{Cell, RefCell},
@@ -7,16 +12,32 @@ use std::{
 };
 
 use crate::{
-    system::{STATES, StateData, StateId},
+    system::{
+        Created, EqPolicy, STATES, StateData, caller, clear_view_reads, free_state, mark_rebuilt,
+    },
     view::{View, ViewId, stateful::state::State},
 };
 
 pub struct Stateful<StateFn, InnerFn, const QUIET: bool> {
     state_fn: Cell<Option<StateFn>>,
     inner_fn: InnerFn,
+    eq: Option<EqPolicy>,
+    created: Created,
+}
+impl<StateFn, InnerFn, const QUIET: bool> Stateful<StateFn, InnerFn, QUIET> {
+    /// Makes writes that leave the value equal skip notifying, like `set_if_changed`.
+    pub fn skip_equal<T>(mut self) -> Self
+    where
+        StateFn: FnOnce() -> T,
+        T: PartialEq + Clone + 'static,
+    {
+        self.eq = Some(EqPolicy::of::<T>());
+        self
+    }
 }
 pub struct StatefulViewState<T: 'static, Inner: View> {
     state: State<T>,
+    path: Rc<[ViewId]>,
     inner: Inner,
     inner_state: Inner::ViewState,
     inner_id: ViewId,
@@ -42,13 +63,20 @@ where
         let id = STATES.with_borrow_mut(|states| {
             states.insert(StateData {
                 value: Rc::new(RefCell::new(value)),
-                path,
+                path: path.clone(),
+                app_id: ctx.app_id,
+                dependents: vec![],
+                subscribers: vec![],
+                eq: self.eq,
+                created: self.created,
+                view_read: false,
             })
         });
         let state = State {
             state_id: id,
             app_id: ctx.app_id,
             quiet: QUIET,
+            created: self.created,
             _p: PhantomData,
         };
         let inner = (self.inner_fn)(state);
@@ -56,6 +84,7 @@ where
         let inner_state = ctx.with_id(inner_id, |ctx| inner.build(ctx, anchor, anchor_type));
         StatefulViewState {
             state,
+            path,
             inner,
             inner_state,
             inner_id,
@@ -70,6 +99,8 @@ where
         anchor: &mut godot::prelude::Node,
         anchor_type: super::AnchorType,
     ) {
+        mark_rebuilt(ctx.app_id, &state.path);
+        clear_view_reads(state.state.state_id);
         let inner = (self.inner_fn)(state.state);
         ctx.with_id(state.inner_id, |ctx| {
             inner.rebuild(
@@ -95,7 +126,7 @@ where
                 .inner
                 .teardown(&mut state.inner_state, ctx, anchor, anchor_type);
         });
-        STATES.with_borrow_mut(|states| states.remove(state.state.state_id));
+        free_state(state.state.state_id);
     }
 
     fn notify_state(
@@ -119,6 +150,8 @@ where
                 });
             }
         } else {
+            mark_rebuilt(ctx.app_id, &state.path);
+            clear_view_reads(state.state.state_id);
             let new = (self.inner_fn)(state.state);
             ctx.with_id(state.inner_id, |ctx| {
                 new.rebuild(
@@ -142,6 +175,7 @@ where
     }
 }
 
+#[track_caller]
 pub fn stateful<T, Inner, StateFn, InnerFn>(
     init: StateFn,
     view: InnerFn,
@@ -155,8 +189,11 @@ where
     Stateful {
         state_fn: Cell::new(Some(init)),
         inner_fn: view,
+        eq: None,
+        created: caller(),
     }
 }
+#[track_caller]
 pub fn stateful_quiet<T, Inner, StateFn, InnerFn>(
     init: StateFn,
     view: InnerFn,
@@ -170,5 +207,7 @@ where
     Stateful {
         state_fn: Cell::new(Some(init)),
         inner_fn: view,
+        eq: None,
+        created: caller(),
     }
 }
@@ -0,0 +1,312 @@
+use std::{
+    cell::Cell,
+    marker::PhantomData,
+    rc::{Rc, Weak},
+};
+
+use godot::{
+    builtin::Callable,
+    classes::{ConfigFile, Engine, SceneTree},
+    global::{Error, godot_error, godot_warn},
+    meta::{FromGodot, ToGodot},
+    obj::{NewGd, Singleton},
+};
+
+use crate::{
+    State, View,
+    reactive::{Reactive, Subscription},
+    view::stateful::stateful,
+};
+
+/// How long to wait after the last change before writing to disk.
+const SAVE_DELAY: f64 = 0.5;
+const SECTION: &str = "lunar";
+
+trait Backend<T> {
+    /// `Ok(None)` if there is nothing saved yet.
+    fn load(path: &str, key: &str) -> Result<Option<T>, String>;
+    fn save(path: &str, key: &str, value: &T) -> Result<(), String>;
+}
+
+struct ConfigBackend;
+impl<T: ToGodot + FromGodot> Backend<T> for ConfigBackend {
+    fn load(path: &str, key: &str) -> Result<Option<T>, String> {
+        let mut file = ConfigFile::new_gd();
+        match file.load(path) {
+            Error::OK => {}
+            Error::ERR_FILE_NOT_FOUND => return Ok(None),
+            err => return Err(format!("{err:?}")),
+        }
+        if !file.has_section_key(SECTION, key) {
+            return Ok(None);
+        }
+        T::try_from_variant(&file.get_value(SECTION, key))
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+    fn save(path: &str, key: &str, value: &T) -> Result<(), String> {
+        // keep the other keys in the file
+        let mut file = ConfigFile::new_gd();
+        let _ = file.load(path);
+        file.set_value(SECTION, key, &value.to_variant());
+        match file.save(path) {
+            Error::OK => Ok(()),
+            err => Err(format!("{err:?}")),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct JsonBackend;
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> Backend<T> for JsonBackend {
+    fn load(path: &str, key: &str) -> Result<Option<T>, String> {
+        use godot::classes::FileAccess;
+
+        if !FileAccess::file_exists(path) {
+            return Ok(None);
+        }
+        let text = FileAccess::get_file_as_string(path).to_string();
+        let mut map: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&text).map_err(|e| e.to_string())?;
+        match map.remove(key) {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| e.to_string()),
+            None => Ok(None),
+        }
+    }
+    fn save(path: &str, key: &str, value: &T) -> Result<(), String> {
+        use godot::classes::{FileAccess, file_access::ModeFlags};
+
+        // keep the other keys in the file, unless it can't be read anyway
+        let mut map = if FileAccess::file_exists(path) {
+            serde_json::from_str(&FileAccess::get_file_as_string(path).to_string())
+                .unwrap_or_default()
+        } else {
+            serde_json::Map::new()
+        };
+        map.insert(
+            key.to_string(),
+            serde_json::to_value(value).map_err(|e| e.to_string())?,
+        );
+        let text = serde_json::to_string_pretty(&map).map_err(|e| e.to_string())?;
+        let mut file = FileAccess::open(path, ModeFlags::WRITE)
+            .ok_or_else(|| format!("{:?}", FileAccess::get_open_error()))?;
+        if file.store_string(&text) {
+            Ok(())
+        } else {
+            Err(format!("{:?}", file.get_error()))
+        }
+    }
+}
+
+fn load<T, B: Backend<T>>(path: &str, key: &str, default: impl FnOnce() -> T) -> T {
+    match B::load(path, key) {
+        Ok(Some(value)) => value,
+        Ok(None) => {
+            godot_warn!("no saved value for `{key}` in {path}, using the default");
+            default()
+        }
+        Err(err) => {
+            godot_error!("could not load `{key}` from {path}, using the default: {err}");
+            default()
+        }
+    }
+}
+
+struct Saver<T: 'static, B> {
+    state: State<T>,
+    path: String,
+    key: String,
+    generation: Cell<u64>,
+    pending: Cell<bool>,
+    _p: PhantomData<B>,
+}
+
+impl<T, B: Backend<T> + 'static> Saver<T, B> {
+    fn flush(&self) {
+        if !self.pending.replace(false) || !self.state.is_valid() {
+            return;
+        }
+        if let Err(err) = self.state.with(|v| B::save(&self.path, &self.key, v)) {
+            godot_error!("could not save `{}` to {}: {err}", self.key, self.path);
+        }
+    }
+    /// Saves after `SAVE_DELAY` without another change.
+    fn schedule(self: &Rc<Self>) {
+        self.pending.set(true);
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+
+        let timer = Engine::singleton()
+            .get_main_loop()
+            .and_then(|l| l.try_cast::<SceneTree>().ok())
+            .and_then(|mut tree| tree.create_timer(SAVE_DELAY));
+        let Some(mut timer) = timer else {
+            self.flush();
+            return;
+        };
+        let this: Weak<Self> = Rc::downgrade(self);
+        timer.connect(
+            "timeout",
+            &Callable::from_fn("lunar_persist", move |_| {
+                if let Some(this) = this.upgrade()
+                    && this.generation.get() == generation
+                {
+                    this.flush();
+                }
+            }),
+        );
+    }
+}
+
+struct Persist<T: 'static, B> {
+    state: State<T>,
+    path: String,
+    key: String,
+    _p: PhantomData<B>,
+}
+
+struct PersistViewState<T: 'static, B> {
+    saver: Rc<Saver<T, B>>,
+    subscription: Option<Subscription>,
+}
+
+impl<T: 'static, B: Backend<T> + 'static> View for Persist<T, B> {
+    type ViewState = PersistViewState<T, B>;
+
+    fn build(
+        &self,
+        ctx: &mut crate::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::AnchorType,
+    ) -> Self::ViewState {
+        let saver = Rc::new(Saver {
+            state: self.state,
+            path: self.path.clone(),
+            key: self.key.clone(),
+            generation: Cell::new(0),
+            pending: Cell::new(false),
+            _p: PhantomData,
+        });
+        let weak = Rc::downgrade(&saver);
+        let subscription = self.state.watch(move || {
+            if let Some(saver) = weak.upgrade() {
+                saver.schedule();
+            }
+        });
+        PersistViewState {
+            saver,
+            subscription: Some(subscription),
+        }
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::AnchorType,
+    ) {
+    }
+
+    fn teardown(
+        &self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::AnchorType,
+    ) {
+        state.subscription = None;
+        state.saver.flush();
+    }
+
+    fn notify_state(
+        &self,
+        path: &[crate::ViewId],
+        state: &mut Self::ViewState,
+        ctx: &mut crate::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::AnchorType,
+    ) {
+    }
+
+    fn collect_nodes(
+        &self,
+        state: &Self::ViewState,
+        nodes: &mut Vec<godot::prelude::Gd<godot::prelude::Node>>,
+    ) {
+    }
+}
+
+#[track_caller]
+fn persisted<B, T, Inner, StateFn, InnerFn>(
+    path: &str,
+    key: &str,
+    default: StateFn,
+    view: InnerFn,
+) -> impl View + use<B, T, Inner, StateFn, InnerFn>
+where
+    B: Backend<T> + 'static,
+    T: 'static,
+    StateFn: FnOnce() -> T,
+    InnerFn: Fn(State<T>) -> Inner,
+    Inner: View,
+{
+    let (path, key) = (path.to_string(), key.to_string());
+    let (load_path, load_key) = (path.clone(), key.clone());
+    stateful(
+        move || load::<T, B>(&load_path, &load_key, default),
+        move |state| {
+            (
+                Persist::<T, B> {
+                    state,
+                    path: path.clone(),
+                    key: key.clone(),
+                    _p: PhantomData,
+                },
+                view(state),
+            )
+        },
+    )
+}
+
+/// A state that is loaded from the `ConfigFile` at `path` when built, and saved back
+/// shortly after every change and on teardown.
+///
+/// A missing or unreadable value is reported and replaced by `default`.
+#[track_caller]
+pub fn stateful_persisted<T, Inner, StateFn, InnerFn>(
+    path: &str,
+    key: &str,
+    default: StateFn,
+    view: InnerFn,
+) -> impl View + use<T, Inner, StateFn, InnerFn>
+where
+    T: ToGodot + FromGodot + 'static,
+    StateFn: FnOnce() -> T,
+    InnerFn: Fn(State<T>) -> Inner,
+    Inner: View,
+{
+    persisted::<ConfigBackend, _, _, _, _>(path, key, default, view)
+}
+
+/// Like `stateful_persisted`, but stores the value as JSON with serde.
+#[cfg(feature = "serde")]
+#[track_caller]
+pub fn stateful_persisted_json<T, Inner, StateFn, InnerFn>(
+    path: &str,
+    key: &str,
+    default: StateFn,
+    view: InnerFn,
+) -> impl View + use<T, Inner, StateFn, InnerFn>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + 'static,
+    StateFn: FnOnce() -> T,
+    InnerFn: Fn(State<T>) -> Inner,
+    Inner: View,
+{
+    persisted::<JsonBackend, _, _, _, _>(path, key, default, view)
+}
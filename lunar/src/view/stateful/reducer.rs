@@ -0,0 +1,324 @@
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    rc::Rc,
+};
+
+use crate::{
+    State, StateError,
+    system::{Created, STATES, StateData, caller, clear_view_reads, free_state, mark_rebuilt},
+    view::{View, ViewId},
+};
+
+type ReduceFn<T, A> = Rc<dyn Fn(&mut T, A)>;
+pub type Middleware<T, A> = Rc<dyn Fn(Reducer<T, A>, A, &mut dyn FnMut(A))>;
+
+pub struct ReducerData<T: 'static, A: 'static> {
+    value: T,
+    reduce: ReduceFn<T, A>,
+    middleware: Rc<[Middleware<T, A>]>,
+}
+
+/// A state that is only changed by dispatching actions to its reduce function.
+pub struct Reducer<T: 'static, A: 'static> {
+    pub(crate) state: State<ReducerData<T, A>>,
+}
+impl<T, A> Copy for Reducer<T, A> {}
+impl<T, A> Clone for Reducer<T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, A> Reducer<T, A> {
+    pub fn is_valid(&self) -> bool {
+        self.state.is_valid()
+    }
+    pub fn try_with<R, F>(&self, f: F) -> Result<R, StateError>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.state.try_with(|d| f(&d.value))
+    }
+    pub fn try_get(&self) -> Result<T, StateError>
+    where
+        T: Copy,
+    {
+        self.try_with(|v| *v)
+    }
+    pub fn try_get_clone(&self) -> Result<T, StateError>
+    where
+        T: Clone,
+    {
+        self.try_with(|v| v.clone())
+    }
+    #[track_caller]
+    pub fn get(&self) -> T
+    where
+        T: Copy,
+    {
+        self.state.with(|d| d.value)
+    }
+    #[track_caller]
+    pub fn get_clone(&self) -> T
+    where
+        T: Clone,
+    {
+        self.state.with(|d| d.value.clone())
+    }
+    #[track_caller]
+    pub fn with<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.state.with(|d| f(&d.value))
+    }
+    /// Runs the action through the middleware, then the reduce function.
+    #[track_caller]
+    pub fn dispatch(&self, action: A) {
+        let middleware = self.state.with(|d| d.middleware.clone());
+        self.run(&middleware, action);
+    }
+    #[track_caller]
+    fn run(&self, middleware: &[Middleware<T, A>], action: A) {
+        match middleware.split_first() {
+            Some((first, rest)) => first(*self, action, &mut |action| self.run(rest, action)),
+            None => self.state.update(|d| {
+                let ReducerData { value, reduce, .. } = d;
+                reduce(value, action)
+            }),
+        }
+    }
+    /// A handle that only dispatches, for views that don't need to know `T`.
+    pub fn dispatcher(&self) -> Dispatch<A> {
+        let reducer = *self;
+        Dispatch {
+            dispatch: Rc::new(move |action| reducer.dispatch(action)),
+        }
+    }
+}
+
+pub struct Dispatch<A> {
+    dispatch: Rc<dyn Fn(A)>,
+}
+impl<A> Clone for Dispatch<A> {
+    fn clone(&self) -> Self {
+        Self {
+            dispatch: self.dispatch.clone(),
+        }
+    }
+}
+impl<A> Dispatch<A> {
+    pub fn dispatch(&self, action: A) {
+        (self.dispatch)(action)
+    }
+}
+
+pub struct StatefulReducer<StateFn, Reduce, InnerFn, T: 'static, A: 'static> {
+    state_fn: Cell<Option<StateFn>>,
+    reduce: Rc<Reduce>,
+    middleware: Vec<Middleware<T, A>>,
+    inner_fn: InnerFn,
+    created: Created,
+}
+impl<StateFn, Reduce, InnerFn, T: 'static, A: 'static>
+    StatefulReducer<StateFn, Reduce, InnerFn, T, A>
+{
+    /// Adds a middleware, which gets every action before the ones added after it and can
+    /// pass it on, change it or drop it by calling `next` or not.
+    pub fn middleware<M>(mut self, middleware: M) -> Self
+    where
+        M: Fn(Reducer<T, A>, A, &mut dyn FnMut(A)) + 'static,
+    {
+        self.middleware.push(Rc::new(middleware));
+        self
+    }
+}
+
+pub struct StatefulReducerViewState<T: 'static, A: 'static, Inner: View> {
+    reducer: Reducer<T, A>,
+    path: Rc<[ViewId]>,
+    inner: Inner,
+    inner_state: Inner::ViewState,
+    inner_id: ViewId,
+}
+
+impl<StateFn, Reduce, InnerFn, T, A> StatefulReducer<StateFn, Reduce, InnerFn, T, A>
+where
+    T: 'static,
+    A: 'static,
+    Reduce: Fn(&mut T, A) + 'static,
+{
+    /// The closures might capture different values after a rebuild.
+    fn refresh(&self, reducer: Reducer<T, A>) {
+        let value = STATES.with_borrow(|states| states[reducer.state.state_id].value.clone());
+        let mut value = value.borrow_mut();
+        let data = value.downcast_mut::<ReducerData<T, A>>().unwrap();
+        data.reduce = self.reduce.clone();
+        data.middleware = self.middleware.clone().into();
+    }
+}
+
+impl<StateFn, Reduce, InnerFn, T, A, Inner> View for StatefulReducer<StateFn, Reduce, InnerFn, T, A>
+where
+    T: 'static,
+    A: 'static,
+    StateFn: FnOnce() -> T,
+    Reduce: Fn(&mut T, A) + 'static,
+    InnerFn: Fn(Reducer<T, A>) -> Inner,
+    Inner: View,
+{
+    type ViewState = StatefulReducerViewState<T, A, Inner>;
+
+    fn build(
+        &self,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::AnchorType,
+    ) -> Self::ViewState {
+        let value = ReducerData {
+            value: self.state_fn.take().unwrap()(),
+            reduce: self.reduce.clone() as ReduceFn<T, A>,
+            middleware: self.middleware.clone().into(),
+        };
+        let path: Rc<[ViewId]> = ctx.path.clone().into();
+        let id = STATES.with_borrow_mut(|states| {
+            states.insert(StateData {
+                value: Rc::new(RefCell::new(value)),
+                path: path.clone(),
+                app_id: ctx.app_id,
+                dependents: vec![],
+                subscribers: vec![],
+                eq: None,
+                created: self.created,
+                view_read: false,
+            })
+        });
+        let reducer = Reducer {
+            state: State {
+                state_id: id,
+                app_id: ctx.app_id,
+                quiet: false,
+                created: self.created,
+                _p: PhantomData,
+            },
+        };
+        let inner = (self.inner_fn)(reducer);
+        let inner_id = ctx.new_structural_id();
+        let inner_state = ctx.with_id(inner_id, |ctx| inner.build(ctx, anchor, anchor_type));
+        StatefulReducerViewState {
+            reducer,
+            path,
+            inner,
+            inner_state,
+            inner_id,
+        }
+    }
+
+    fn rebuild(
+        &self,
+        _prev: &Self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::AnchorType,
+    ) {
+        self.refresh(state.reducer);
+        mark_rebuilt(ctx.app_id, &state.path);
+        clear_view_reads(state.reducer.state.state_id);
+        let inner = (self.inner_fn)(state.reducer);
+        ctx.with_id(state.inner_id, |ctx| {
+            inner.rebuild(
+                &state.inner,
+                &mut state.inner_state,
+                ctx,
+                anchor,
+                anchor_type,
+            );
+        });
+        state.inner = inner;
+    }
+
+    fn teardown(
+        &self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::AnchorType,
+    ) {
+        ctx.with_id(state.inner_id, |ctx| {
+            state
+                .inner
+                .teardown(&mut state.inner_state, ctx, anchor, anchor_type);
+        });
+        free_state(state.reducer.state.state_id);
+    }
+
+    fn notify_state(
+        &self,
+        path: &[ViewId],
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: crate::AnchorType,
+    ) {
+        if let Some((start, rest)) = path.split_first() {
+            if *start == state.inner_id {
+                ctx.with_id(state.inner_id, |ctx| {
+                    state.inner.notify_state(
+                        rest,
+                        &mut state.inner_state,
+                        ctx,
+                        anchor,
+                        anchor_type,
+                    );
+                });
+            }
+        } else {
+            mark_rebuilt(ctx.app_id, &state.path);
+            clear_view_reads(state.reducer.state.state_id);
+            let new = (self.inner_fn)(state.reducer);
+            ctx.with_id(state.inner_id, |ctx| {
+                new.rebuild(
+                    &state.inner,
+                    &mut state.inner_state,
+                    ctx,
+                    anchor,
+                    anchor_type,
+                );
+            });
+            state.inner = new;
+        }
+    }
+
+    fn collect_nodes(
+        &self,
+        state: &Self::ViewState,
+        nodes: &mut Vec<godot::prelude::Gd<godot::prelude::Node>>,
+    ) {
+        state.inner.collect_nodes(&state.inner_state, nodes);
+    }
+}
+
+#[track_caller]
+pub fn stateful_reducer<T, A, Inner, StateFn, Reduce, InnerFn>(
+    init: StateFn,
+    reduce: Reduce,
+    view: InnerFn,
+) -> StatefulReducer<StateFn, Reduce, InnerFn, T, A>
+where
+    T: 'static,
+    A: 'static,
+    StateFn: FnOnce() -> T,
+    Reduce: Fn(&mut T, A) + 'static,
+    InnerFn: Fn(Reducer<T, A>) -> Inner,
+    Inner: View,
+{
+    StatefulReducer {
+        state_fn: Cell::new(Some(init)),
+        reduce: Rc::new(reduce),
+        middleware: vec![],
+        inner_fn: view,
+        created: caller(),
+    }
+}
@@ -0,0 +1,84 @@
+use std::{
+    marker::PhantomData,
+    sync::mpsc::{self, Sender},
+};
+
+use crate::{
+    State,
+    system::{APP_CHANNELS, AppId, AppMessage, Created, StateId},
+};
+
+/// A handle to a [`State`] that can be moved to other threads.
+///
+/// Updates are queued and applied on the main thread by the next `App::run`.
+/// Sending to a state or app that no longer exists does nothing.
+pub struct StateSender<T: 'static> {
+    sender: Sender<AppMessage>,
+    state_id: StateId,
+    app_id: AppId,
+    quiet: bool,
+    created: Created,
+    _p: PhantomData<fn(T) -> T>,
+}
+impl<T> Clone for StateSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            state_id: self.state_id,
+            app_id: self.app_id,
+            quiet: self.quiet,
+            created: self.created,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<T> State<T> {
+    pub fn sender(&self) -> StateSender<T> {
+        let sender = APP_CHANNELS
+            .with_borrow(|map| map.get(self.app_id).map(|c| c.sender.clone()))
+            .unwrap_or_else(|| mpsc::channel().0);
+        StateSender {
+            sender,
+            state_id: self.state_id,
+            app_id: self.app_id,
+            quiet: self.quiet,
+            created: self.created,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<T> StateSender<T> {
+    fn send<F>(&self, f: F)
+    where
+        F: FnOnce(State<T>) + Send + 'static,
+    {
+        let (state_id, app_id, quiet, created) =
+            (self.state_id, self.app_id, self.quiet, self.created);
+        let _ = self.sender.send(Box::new(move || {
+            let state = State {
+                state_id,
+                app_id,
+                quiet,
+                created,
+                _p: PhantomData,
+            };
+            if state.is_valid() {
+                f(state);
+            }
+        }));
+    }
+    pub fn set(&self, to: T)
+    where
+        T: Send,
+    {
+        self.send(move |state| state.set(to));
+    }
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut T) + Send + 'static,
+    {
+        self.send(move |state| state.update(f));
+    }
+}
@@ -1,9 +1,19 @@
-use std::{marker::PhantomData, rc::Rc};
+use std::{
+    any::Any,
+    cell::RefCell,
+    fmt::{self, Display},
+    marker::PhantomData,
+    rc::Rc,
+};
 
 use godot::global::godot_print;
 
 use crate::{
-    system::{AppId, StateId, APPS, APP_NOTIFICATIONS, STATES},
+    reactive::{Reactive, Subscription},
+    system::{
+        APPS, AppId, Created, EqPolicy, STATES, StateId, current_app, describe_created, notify,
+        track_read,
+    },
     view::AnchorType,
 };
 
@@ -11,6 +21,7 @@ pub struct State<T: 'static> {
     pub(crate) state_id: StateId,
     pub(crate) app_id: AppId,
     pub(crate) quiet: bool,
+    pub(crate) created: Created,
     pub(crate) _p: PhantomData<*const T>,
 }
 impl<T> Copy for State<T> {}
@@ -20,69 +31,294 @@ impl<T> Clone for State<T> {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateError {
+    /// The view that owned the state was torn down, or its app stopped.
+    Stale,
+    /// The value is already borrowed, e.g. by an `update` call further up the stack.
+    Borrowed,
+    WrongType,
+    /// The state belongs to another app than the one being built or run.
+    DifferentApp,
+}
+impl Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StateError::Stale => "the state was already freed",
+            StateError::Borrowed => "the state is already borrowed, e.g. by an `update` call",
+            StateError::WrongType => "the state holds a value of another type",
+            StateError::DifferentApp => "the state belongs to a different app",
+        })
+    }
+}
+impl std::error::Error for StateError {}
+
+type StateValue = (Rc<RefCell<dyn Any>>, Option<EqPolicy>);
+
+/// The value of a state, as long as it's alive and belongs to the current app.
+pub(crate) fn state_data(state_id: StateId, app_id: AppId) -> Result<StateValue, StateError> {
+    if current_app().is_some_and(|app| app != app_id) {
+        return Err(StateError::DifferentApp);
+    }
+    STATES
+        .with_borrow(|states| states.get(state_id).map(|s| (s.value.clone(), s.eq)))
+        .ok_or(StateError::Stale)
+}
+
+/// Panics with where the state lives and was created.
+#[track_caller]
+pub(crate) fn unwrap_state<R>(
+    state_id: StateId,
+    created: Created,
+    result: Result<R, StateError>,
+) -> R {
+    // not `unwrap_or_else`, the panic would point at the closure
+    match result {
+        Ok(value) => value,
+        Err(err) => {
+            let path = STATES.with_borrow(|states| {
+                states
+                    .get(state_id)
+                    .map(|s| format!(" at {:?}", s.path))
+                    .unwrap_or_default()
+            });
+            panic!("{err}{path}{}", describe_created(created))
+        }
+    }
+}
+
+/// Writes to the value through `f`, then notifies unless the state's `EqPolicy` finds it
+/// unchanged. `f` returns `None` if the value isn't of the type it expects.
+pub(crate) fn update_state<R>(
+    state_id: StateId,
+    app_id: AppId,
+    quiet: bool,
+    f: impl FnOnce(&mut dyn Any) -> Option<R>,
+) -> Result<R, StateError> {
+    let (value, eq) = state_data(state_id, app_id)?;
+    let mut value = value.try_borrow_mut().map_err(|_| StateError::Borrowed)?;
+    let old = eq.map(|eq| (eq.clone)(&*value));
+    let ret = f(&mut *value).ok_or(StateError::WrongType)?;
+    let changed = match (eq, old) {
+        (Some(eq), Some(old)) => !(eq.eq)(&*old, &*value),
+        _ => true,
+    };
+    drop(value);
+    if changed {
+        notify(state_id, quiet);
+    }
+    Ok(ret)
+}
+
 impl<T> State<T> {
     pub fn notify(&self) {
-        if self.quiet {
-            return;
-        }
-        let path = STATES.with_borrow_mut(|states| states[self.state_id].path.clone());
-        APP_NOTIFICATIONS
-            .with_borrow_mut(|map| map.entry(self.app_id).unwrap().or_default().push(path));
+        notify(self.state_id, self.quiet);
+    }
+    /// Calls `cb` with the new value every time the state changes, from the next
+    /// `App::run` before any views are rebuilt.
+    ///
+    /// Stops when the subscription is dropped or the state is freed.
+    pub fn subscribe<F>(&self, cb: F) -> Subscription
+    where
+        F: Fn(&T) + 'static,
+    {
+        let state = *self;
+        self.watch(move || {
+            let _ = state.try_with(&cb);
+        })
     }
     pub fn is_valid(&self) -> bool {
         STATES.with_borrow(|states| states.contains_key(self.state_id))
     }
+    #[track_caller]
+    fn unwrap<R>(&self, result: Result<R, StateError>) -> R {
+        unwrap_state(self.state_id, self.created, result)
+    }
+    fn data(&self) -> Result<StateValue, StateError> {
+        state_data(self.state_id, self.app_id)
+    }
+    /// Writes through `f`, which also returns whether to notify.
+    fn write<R>(
+        &self,
+        f: impl FnOnce(&mut T, Option<EqPolicy>) -> (R, bool),
+    ) -> Result<R, StateError> {
+        let (value, eq) = self.data()?;
+        let mut value = value.try_borrow_mut().map_err(|_| StateError::Borrowed)?;
+        let (ret, changed) = f(value.downcast_mut().ok_or(StateError::WrongType)?, eq);
+        drop(value);
+        if changed {
+            self.notify();
+        }
+        Ok(ret)
+    }
+    pub fn try_with<R, F>(&self, f: F) -> Result<R, StateError>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let (value, _) = self.data()?;
+        track_read(self.state_id);
+        let value = value.try_borrow().map_err(|_| StateError::Borrowed)?;
+        Ok(f(value.downcast_ref().ok_or(StateError::WrongType)?))
+    }
+    pub fn try_get(&self) -> Result<T, StateError>
+    where
+        T: Copy,
+    {
+        self.try_with(|v| *v)
+    }
+    pub fn try_get_clone(&self) -> Result<T, StateError>
+    where
+        T: Clone,
+    {
+        self.try_with(|v| v.clone())
+    }
+    pub fn try_set(&self, to: T) -> Result<(), StateError> {
+        self.write(|value, eq| {
+            let changed = eq.is_none_or(|eq| !(eq.eq)(value, &to));
+            *value = to;
+            ((), changed)
+        })
+    }
+    pub fn try_update<R, F>(&self, f: F) -> Result<R, StateError>
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        update_state(self.state_id, self.app_id, self.quiet, |value| {
+            value.downcast_mut().map(f)
+        })
+    }
+    #[track_caller]
     pub fn get(&self) -> T
     where
         T: Copy,
     {
-        STATES.with_borrow(|states| *states[self.state_id].value.borrow().downcast_ref().unwrap())
+        self.unwrap(self.try_get())
     }
+    #[track_caller]
     pub fn get_clone(&self) -> T
     where
         T: Clone,
     {
-        STATES.with_borrow(|states| {
-            states[self.state_id]
-                .value
-                .borrow()
-                .downcast_ref::<T>()
-                .unwrap()
-                .clone()
-        })
+        self.unwrap(self.try_get_clone())
     }
+    #[track_caller]
     pub fn with<R, F>(&self, f: F) -> R
     where
         F: FnOnce(&T) -> R,
     {
-        let value = STATES.with_borrow_mut(|states| states[self.state_id].value.clone());
-        f(value
-            .try_borrow()
-            .expect("cannot get value during an `update` call")
-            .downcast_ref::<T>()
-            .unwrap())
+        self.unwrap(self.try_with(f))
     }
+    #[track_caller]
     pub fn set(&self, to: T) {
-        STATES.with_borrow(|states| {
-            *states[self.state_id]
-                .value
-                .borrow_mut()
-                .downcast_mut()
-                .unwrap() = to;
-        });
-        self.notify();
+        self.unwrap(self.try_set(to))
     }
+    #[track_caller]
     pub fn update<R, F>(&self, f: F) -> R
     where
         F: FnOnce(&mut T) -> R,
     {
-        let value = STATES.with_borrow_mut(|states| states[self.state_id].value.clone());
-        let ret = f(value
-            .try_borrow_mut()
-            .expect("cannot update value during another `update` call")
-            .downcast_mut::<T>()
-            .unwrap());
-        self.notify();
-        ret
+        self.unwrap(self.try_update(f))
+    }
+    /// Sets the value, only notifying if it differs from the current one.
+    #[track_caller]
+    pub fn set_if_changed(&self, to: T)
+    where
+        T: PartialEq,
+    {
+        self.unwrap(self.write(|value, _| {
+            if *value != to {
+                *value = to;
+                ((), true)
+            } else {
+                ((), false)
+            }
+        }))
+    }
+    /// Updates the value, only notifying if it ends up different from before.
+    #[track_caller]
+    pub fn update_if_changed<R, F>(&self, f: F) -> R
+    where
+        T: PartialEq + Clone,
+        F: FnOnce(&mut T) -> R,
+    {
+        self.unwrap(self.write(|value, _| {
+            let old = value.clone();
+            let ret = f(value);
+            let changed = *value != old;
+            (ret, changed)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        StateError,
+        system::{
+            EqPolicy, free_state,
+            testing::{app, state, take_queued},
+            with_current_app,
+        },
+    };
+
+    #[test]
+    fn eq_state_skips_equal_writes() {
+        let app = app();
+        let count = state(app, &[], 1, Some(EqPolicy::of::<i32>()));
+        count.set(1);
+        count.update(|v| *v *= 1);
+        assert!(take_queued(app).is_empty());
+
+        count.set(2);
+        assert_eq!(take_queued(app).len(), 1);
+        count.update(|v| *v += 1);
+        assert_eq!(take_queued(app).len(), 1);
+    }
+
+    #[test]
+    fn plain_state_notifies_equal_writes() {
+        let app = app();
+        let count = state(app, &[], 1, None);
+        count.set(1);
+        assert_eq!(take_queued(app).len(), 1);
+    }
+
+    #[test]
+    fn if_changed_writes_compare_without_policy() {
+        let app = app();
+        let count = state(app, &[], 1, None);
+        count.set_if_changed(1);
+        count.update_if_changed(|v| *v *= 1);
+        assert!(take_queued(app).is_empty());
+
+        count.set_if_changed(2);
+        assert_eq!(take_queued(app).len(), 1);
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn try_accessors_report_errors() {
+        let app = app();
+        let count = state(app, &[], 1, None);
+        assert_eq!(count.try_get(), Ok(1));
+        assert_eq!(count.with(|_| count.try_set(2)), Err(StateError::Borrowed));
+        assert_eq!(count.update(|_| count.try_get()), Err(StateError::Borrowed));
+        let other = self::app();
+        assert_eq!(
+            with_current_app(other, || count.try_get()),
+            Err(StateError::DifferentApp)
+        );
+
+        drop(free_state(count.state_id));
+        assert_eq!(count.try_get(), Err(StateError::Stale));
+        assert_eq!(count.try_update(|v| *v += 1), Err(StateError::Stale));
+    }
+
+    #[test]
+    #[should_panic(expected = "the state was already freed (created at")]
+    fn panics_name_where_the_state_was_created() {
+        let count = state(app(), &[], 1, None);
+        drop(free_state(count.state_id));
+        count.get();
     }
 }
@@ -0,0 +1,228 @@
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use crate::{
+    State, StateError,
+    system::{AppId, Created, STATES, StateId, notify, track_read},
+    view::stateful::state::{state_data, unwrap_state, update_state},
+};
+
+type Project<U> = Box<dyn for<'a> FnMut(&'a mut dyn Any) -> Option<&'a mut U>>;
+
+/// How a `SubState` gets to its part of the state's value. Reads go through it too, so
+/// they need the value mutably borrowed.
+pub(crate) struct Lens<U>(RefCell<Project<U>>);
+impl<U> Lens<U> {
+    fn new(project: Project<U>) -> Rc<Self> {
+        Rc::new(Lens(RefCell::new(project)))
+    }
+    fn project<'a>(&self, value: &'a mut dyn Any) -> Option<&'a mut U> {
+        (self.0.borrow_mut())(value)
+    }
+}
+
+/// A handle to part of a state, made with `State::map_mut`.
+///
+/// Writes through it notify the state it was made from.
+pub struct SubState<U: 'static> {
+    pub(crate) state_id: StateId,
+    app_id: AppId,
+    quiet: bool,
+    created: Created,
+    pub(crate) lens: Rc<Lens<U>>,
+}
+impl<U> Clone for SubState<U> {
+    fn clone(&self) -> Self {
+        Self {
+            state_id: self.state_id,
+            app_id: self.app_id,
+            quiet: self.quiet,
+            created: self.created,
+            lens: self.lens.clone(),
+        }
+    }
+}
+
+impl<T> State<T> {
+    /// Makes a handle to part of the value, e.g. `player.map_mut(|p| &mut p.name)`.
+    pub fn map_mut<U, F>(&self, mut f: F) -> SubState<U>
+    where
+        F: FnMut(&mut T) -> &mut U + 'static,
+    {
+        SubState {
+            state_id: self.state_id,
+            app_id: self.app_id,
+            quiet: self.quiet,
+            created: self.created,
+            lens: Lens::new(Box::new(move |value| value.downcast_mut().map(&mut f))),
+        }
+    }
+}
+
+impl<U> SubState<U> {
+    pub fn map_mut<V, F>(&self, mut f: F) -> SubState<V>
+    where
+        F: FnMut(&mut U) -> &mut V + 'static,
+    {
+        let lens = self.lens.clone();
+        SubState {
+            state_id: self.state_id,
+            app_id: self.app_id,
+            quiet: self.quiet,
+            created: self.created,
+            lens: Lens::new(Box::new(move |value| lens.project(value).map(&mut f))),
+        }
+    }
+    pub fn notify(&self) {
+        notify(self.state_id, self.quiet);
+    }
+    pub fn is_valid(&self) -> bool {
+        STATES.with_borrow(|states| states.contains_key(self.state_id))
+    }
+    #[track_caller]
+    fn unwrap<R>(&self, result: Result<R, StateError>) -> R {
+        unwrap_state(self.state_id, self.created, result)
+    }
+    pub fn try_with<R, F>(&self, f: F) -> Result<R, StateError>
+    where
+        F: FnOnce(&U) -> R,
+    {
+        let (value, _) = state_data(self.state_id, self.app_id)?;
+        track_read(self.state_id);
+        let mut value = value.try_borrow_mut().map_err(|_| StateError::Borrowed)?;
+        Ok(f(self
+            .lens
+            .project(&mut *value)
+            .ok_or(StateError::WrongType)?))
+    }
+    pub fn try_get(&self) -> Result<U, StateError>
+    where
+        U: Copy,
+    {
+        self.try_with(|v| *v)
+    }
+    pub fn try_get_clone(&self) -> Result<U, StateError>
+    where
+        U: Clone,
+    {
+        self.try_with(|v| v.clone())
+    }
+    pub fn try_set(&self, to: U) -> Result<(), StateError> {
+        self.try_update(|v| *v = to)
+    }
+    pub fn try_update<R, F>(&self, f: F) -> Result<R, StateError>
+    where
+        F: FnOnce(&mut U) -> R,
+    {
+        // the state's eq policy compares the whole value, like writes through the `State`
+        update_state(self.state_id, self.app_id, self.quiet, |value| {
+            self.lens.project(value).map(f)
+        })
+    }
+    #[track_caller]
+    pub fn get(&self) -> U
+    where
+        U: Copy,
+    {
+        self.unwrap(self.try_get())
+    }
+    #[track_caller]
+    pub fn get_clone(&self) -> U
+    where
+        U: Clone,
+    {
+        self.unwrap(self.try_get_clone())
+    }
+    #[track_caller]
+    pub fn with<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&U) -> R,
+    {
+        self.unwrap(self.try_with(f))
+    }
+    #[track_caller]
+    pub fn set(&self, to: U) {
+        self.unwrap(self.try_set(to))
+    }
+    #[track_caller]
+    pub fn update<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut U) -> R,
+    {
+        self.unwrap(self.try_update(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::{
+        StateError, ViewId,
+        system::{
+            EqPolicy, free_state,
+            testing::{app, state, take_queued},
+        },
+    };
+
+    #[derive(Clone, PartialEq)]
+    struct Player {
+        name: String,
+        hp: i32,
+    }
+
+    fn player() -> Player {
+        Player {
+            name: "a".into(),
+            hp: 10,
+        }
+    }
+
+    #[test]
+    fn writes_follow_the_eq_policy() {
+        let app = app();
+        let player = state(app, &[], player(), Some(EqPolicy::of::<Player>()));
+        let hp = player.map_mut(|p| &mut p.hp);
+        hp.set(10);
+        hp.update(|hp| *hp += 0);
+        assert!(take_queued(app).is_empty());
+
+        hp.set(5);
+        assert_eq!(take_queued(app).len(), 1);
+    }
+
+    #[test]
+    fn reads_and_writes_part_of_the_state() {
+        let app = app();
+        let path = [ViewId::Structural(1)];
+        let player = state(app, &path, player(), None);
+        let name = player.map_mut(|p| &mut p.name);
+        assert_eq!(name.get_clone(), "a");
+
+        name.set("b".into());
+        assert_eq!(player.with(|p| p.name.clone()), "b");
+        // the write notifies the stateful owning the whole state
+        assert_eq!(take_queued(app), vec![Rc::from(&path[..])]);
+    }
+
+    #[test]
+    fn map_mut_chains() {
+        let player = state(app(), &[], (player(), 1), None);
+        let hp = player.map_mut(|t| &mut t.0).map_mut(|p| &mut p.hp);
+        hp.update(|hp| *hp -= 3);
+        assert_eq!(player.with(|t| t.0.hp), 7);
+        assert_eq!(hp.get(), 7);
+    }
+
+    #[test]
+    fn stale_and_borrowed_handles_error() {
+        let player = state(app(), &[], player(), None);
+        let hp = player.map_mut(|p| &mut p.hp);
+        assert_eq!(player.with(|_| hp.try_get()), Err(StateError::Borrowed));
+        assert_eq!(player.with(|_| hp.try_set(1)), Err(StateError::Borrowed));
+
+        drop(free_state(player.state_id));
+        assert!(!hp.is_valid());
+        assert_eq!(hp.try_get(), Err(StateError::Stale));
+        assert_eq!(hp.try_set(1), Err(StateError::Stale));
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    any::TypeId,
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    ops::Deref,
+    rc::Rc,
+};
+
+use crate::{
+    State,
+    system::{
+        APP_STORES, AppId, Created, STATES, STORES, StateData, StoreData, caller, mark_rebuilt,
+    },
+    view::{View, ViewId},
+};
+
+/// A state that belongs to the app instead of a view, one per type.
+///
+/// Reading it through `use_store` only rebuilds the views that did so when it changes.
+pub struct Store<T: 'static> {
+    pub(crate) state: State<T>,
+}
+impl<T> Copy for Store<T> {}
+impl<T> Clone for Store<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Deref for Store<T> {
+    type Target = State<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.state
+    }
+}
+
+pub(crate) fn get_or_create_store<T: 'static>(
+    app_id: AppId,
+    init: impl FnOnce() -> T,
+    created: Created,
+) -> Store<T> {
+    let key = (app_id, TypeId::of::<T>());
+    let id = match APP_STORES.with_borrow(|map| map.get(&key).copied()) {
+        Some(id) => id,
+        None => {
+            let value = init();
+            let id = STATES.with_borrow_mut(|states| {
+                states.insert(StateData {
+                    value: Rc::new(RefCell::new(value)),
+                    path: Rc::new([]),
+                    app_id,
+                    dependents: vec![],
+                    subscribers: vec![],
+                    eq: None,
+                    created,
+                    view_read: false,
+                })
+            });
+            STORES.with_borrow_mut(|stores| {
+                stores.insert(
+                    id,
+                    StoreData {
+                        type_id: key.1,
+                        readers: vec![],
+                    },
+                )
+            });
+            APP_STORES.with_borrow_mut(|map| map.insert(key, id));
+            id
+        }
+    };
+    Store {
+        state: State {
+            state_id: id,
+            app_id,
+            quiet: false,
+            created,
+            _p: PhantomData,
+        },
+    }
+}
+
+pub struct UseStore<InitFn, InnerFn> {
+    init_fn: Cell<Option<InitFn>>,
+    inner_fn: InnerFn,
+    created: Created,
+}
+pub struct UseStoreViewState<T: 'static, Inner: View> {
+    store: Store<T>,
+    path: Rc<[ViewId]>,
+    inner: Inner,
+    inner_state: Inner::ViewState,
+    inner_id: ViewId,
+}
+
+impl<InitFn, InnerFn, T, Inner> View for UseStore<InitFn, InnerFn>
+where
+    T: 'static,
+    InitFn: FnOnce() -> T,
+    InnerFn: Fn(Store<T>) -> Inner,
+    Inner: View,
+{
+    type ViewState = UseStoreViewState<T, Inner>;
+
+    fn build(
+        &self,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) -> Self::ViewState {
+        let store = get_or_create_store(ctx.app_id, self.init_fn.take().unwrap(), self.created);
+        let path: Rc<[ViewId]> = ctx.path.clone().into();
+        STORES.with_borrow_mut(|stores| {
+            if let Some(data) = stores.get_mut(store.state_id) {
+                data.readers.push(path.clone());
+            }
+        });
+        let inner = (self.inner_fn)(store);
+        let inner_id = ctx.new_structural_id();
+        let inner_state = ctx.with_id(inner_id, |ctx| inner.build(ctx, anchor, anchor_type));
+        UseStoreViewState {
+            store,
+            path,
+            inner,
+            inner_state,
+            inner_id,
+        }
+    }
+
+    fn rebuild(
+        &self,
+        _prev: &Self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) {
+        mark_rebuilt(ctx.app_id, &state.path);
+        let inner = (self.inner_fn)(state.store);
+        ctx.with_id(state.inner_id, |ctx| {
+            inner.rebuild(
+                &state.inner,
+                &mut state.inner_state,
+                ctx,
+                anchor,
+                anchor_type,
+            );
+        });
+        state.inner = inner;
+    }
+
+    fn teardown(
+        &self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) {
+        ctx.with_id(state.inner_id, |ctx| {
+            state
+                .inner
+                .teardown(&mut state.inner_state, ctx, anchor, anchor_type);
+        });
+        STORES.with_borrow_mut(|stores| {
+            if let Some(data) = stores.get_mut(state.store.state_id) {
+                data.readers.retain(|p| *p != state.path);
+            }
+        });
+    }
+
+    fn notify_state(
+        &self,
+        path: &[ViewId],
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) {
+        if let Some((start, rest)) = path.split_first() {
+            if *start == state.inner_id {
+                ctx.with_id(state.inner_id, |ctx| {
+                    state.inner.notify_state(
+                        rest,
+                        &mut state.inner_state,
+                        ctx,
+                        anchor,
+                        anchor_type,
+                    );
+                });
+            }
+        } else {
+            mark_rebuilt(ctx.app_id, &state.path);
+            let new = (self.inner_fn)(state.store);
+            ctx.with_id(state.inner_id, |ctx| {
+                new.rebuild(
+                    &state.inner,
+                    &mut state.inner_state,
+                    ctx,
+                    anchor,
+                    anchor_type,
+                );
+            });
+            state.inner = new;
+        }
+    }
+
+    fn collect_nodes(
+        &self,
+        state: &Self::ViewState,
+        nodes: &mut Vec<godot::prelude::Gd<godot::prelude::Node>>,
+    ) {
+        state.inner.collect_nodes(&state.inner_state, nodes);
+    }
+}
+
+/// Gets the app's store of type `T`, creating it with `init` if there is none yet, and
+/// rebuilds `view` whenever it changes.
+#[track_caller]
+pub fn use_store<T, Inner, InitFn, InnerFn>(
+    init: InitFn,
+    view: InnerFn,
+) -> UseStore<InitFn, InnerFn>
+where
+    T: 'static,
+    InitFn: FnOnce() -> T,
+    InnerFn: Fn(Store<T>) -> Inner,
+    Inner: View,
+{
+    UseStore {
+        init_fn: Cell::new(Some(init)),
+        inner_fn: view,
+        created: caller(),
+    }
+}
@@ -0,0 +1,81 @@
+use std::{cell::Cell, future::Future};
+
+use crate::{
+    View,
+    executor::{cancel, spawn},
+    system::TaskId,
+};
+
+pub struct Task<F> {
+    future: Cell<Option<F>>,
+}
+
+pub struct TaskViewState {
+    id: TaskId,
+}
+
+impl<F> View for Task<F>
+where
+    F: Future<Output = ()> + 'static,
+{
+    type ViewState = TaskViewState;
+
+    fn build(
+        &self,
+        ctx: &mut crate::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) -> Self::ViewState {
+        TaskViewState {
+            id: spawn(ctx.app_id, self.future.take().unwrap()),
+        }
+    }
+
+    fn rebuild(
+        &self,
+        prev: &Self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) {
+    }
+
+    fn teardown(
+        &self,
+        state: &mut Self::ViewState,
+        ctx: &mut crate::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) {
+        cancel(state.id);
+    }
+
+    fn notify_state(
+        &self,
+        path: &[super::ViewId],
+        state: &mut Self::ViewState,
+        ctx: &mut crate::ctx::Context,
+        anchor: &mut godot::prelude::Node,
+        anchor_type: super::AnchorType,
+    ) {
+    }
+
+    fn collect_nodes(
+        &self,
+        state: &Self::ViewState,
+        nodes: &mut Vec<godot::prelude::Gd<godot::prelude::Node>>,
+    ) {
+    }
+}
+
+/// Spawns `future` when the view is built and drops it on teardown.
+/// The future is polled by `App::run`.
+pub fn task<F>(future: F) -> Task<F>
+where
+    F: Future<Output = ()> + 'static,
+{
+    Task {
+        future: Cell::new(Some(future)),
+    }
+}
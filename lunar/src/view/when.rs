@@ -5,7 +5,7 @@ use godot::{
     obj::{Gd, NewAlloc},
 };
 
-use crate::{AnchorType, View};
+use crate::{AnchorType, View, view::with_anchor};
 
 pub struct When<T, InnerFn> {
     value: T,
@@ -92,13 +92,11 @@ where
         anchor: &mut godot::prelude::Node,
         anchor_type: super::AnchorType,
     ) {
-        let mut when_anchor = state.anchor.clone();
-        state.inner.teardown(
-            &mut state.inner_state,
-            ctx,
-            &mut when_anchor,
-            AnchorType::Before,
-        );
+        with_anchor(&state.anchor, |when_anchor| {
+            state
+                .inner
+                .teardown(&mut state.inner_state, ctx, when_anchor, AnchorType::Before)
+        });
     }
 
     fn notify_state(
@@ -0,0 +1,19 @@
+use godot::classes::{Control, Label, ProgressBar};
+use lunar::{View, view};
+
+fn sources() -> impl View {
+    view! {
+        state text = (String::from("text"), 0);
+        state value = 0.0f64;
+        computed percent: String = format!("{}%", value.get());
+        Control {
+            Label[text <- text.map_mut(|t| &mut t.0)]
+            Label[text <- percent]
+            ProgressBar[value <- value]
+        }
+    }
+}
+
+fn main() {
+    let _ = sources;
+}
@@ -0,0 +1,31 @@
+use godot::{
+    builtin::GString,
+    classes::{CheckBox, HSlider, LineEdit, OptionButton, TextEdit, VBoxContainer},
+};
+use lunar::{View, view};
+
+fn default_signals() -> impl View {
+    view! {
+        state text = GString::new();
+        state value = 0.0f64;
+        state on = false;
+        state selected = 0i64;
+        VBoxContainer {
+            LineEdit[text <=> text]
+            HSlider[value <=> value]
+            CheckBox[button_pressed <=> on]
+            OptionButton[selected <=> selected]
+        }
+    }
+}
+
+fn named_signal() -> impl View {
+    view! {
+        state text = GString::new();
+        TextEdit[text <=> text @text_changed]
+    }
+}
+
+fn main() {
+    let _ = (default_signals, named_signal);
+}
@@ -0,0 +1,11 @@
+use lunar::{View, view};
+
+fn missing() -> impl View {
+    view! {
+        LineEdit[not_a_property? = Some(1)]
+    }
+}
+
+fn main() {
+    let _ = missing;
+}
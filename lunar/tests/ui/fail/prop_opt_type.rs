@@ -0,0 +1,12 @@
+use godot::classes::LineEdit;
+use lunar::{View, view};
+
+fn wrong_type() -> impl View {
+    view! {
+        LineEdit[max_length? = Some("eight")]
+    }
+}
+
+fn main() {
+    let _ = wrong_type;
+}
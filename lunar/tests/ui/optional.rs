@@ -0,0 +1,22 @@
+use godot::{
+    builtin::{Color, EulerOrder},
+    classes::{Control, Label, LineEdit, Node3D},
+};
+use lunar::{View, view};
+
+fn optional() -> impl View {
+    view! {
+        state hint = None::<String>;
+        state highlight = None::<Color>;
+        Control {
+            LineEdit[placeholder_text? = hint.get_clone(), max_length? = Some(8)]
+            Node3D[rotation_order? = Some(EulerOrder::XYZ)]
+            Control[attr not_a_property? = Some(1)]
+            Label[#color:font_color? = highlight.get(), #font_size:font_size? = Some(12)]
+        }
+    }
+}
+
+fn main() {
+    let _ = optional;
+}
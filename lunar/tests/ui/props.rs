@@ -0,0 +1,49 @@
+use godot::{
+    builtin::{EulerOrder, GString, NodePath, PackedVector2Array, StringName, Vector2},
+    classes::{
+        AudioStreamPlayer, Button, CodeEdit, Control, CpuParticles2D, Label, Line2D, LineEdit,
+        Node3D, RemoteTransform2D, Sprite2D, Texture2D,
+        control::SizeFlags,
+        text_server::{AutowrapMode, JustificationFlag},
+    },
+    global::HorizontalAlignment,
+    obj::Gd,
+};
+use lunar::{View, view};
+
+fn categories() -> impl View {
+    view! {
+        Control {
+            // enums and bitfields, from the class and from `global`
+            Label[
+                horizontal_alignment = HorizontalAlignment::CENTER,
+                autowrap_mode = AutowrapMode::WORD,
+                justification_flags = JustificationFlag::KASHIDA | JustificationFlag::WORD_BOUND,
+                max_lines_visible = 3,
+                visible_ratio = 0.5,
+            ]
+            // builtin enums, and properties sharing an indexed setter
+            Node3D[rotation_order = EulerOrder::YXZ]
+            Control[
+                size_flags_horizontal = SizeFlags::EXPAND_FILL,
+                offset_left = 4.0,
+                focus_neighbor_left = NodePath::from(".."),
+            ]
+            // objects, and class names gdext spells differently
+            Sprite2D[texture = None::<Gd<Texture2D>>, hframes = 2]
+            CpuParticles2D[amount = 16, explosiveness = 0.5]
+            // packed and typed arrays
+            Line2D[points = PackedVector2Array::from([Vector2::ZERO, Vector2::ONE])]
+            CodeEdit[indent_size = 2]
+            // strings, string names and node paths
+            LineEdit[text = "text", placeholder_text = GString::from("hint")]
+            Button[text = String::from("ok")]
+            AudioStreamPlayer[bus = StringName::from("Master")]
+            RemoteTransform2D[remote_path = NodePath::from("..")]
+        }
+    }
+}
+
+fn main() {
+    let _ = categories;
+}
@@ -0,0 +1,25 @@
+use godot::classes::{Control, Label};
+use lunar::{State, View, use_context, view};
+
+fn label() -> impl View {
+    view! {
+        computed text: String = use_context::<State<i32>>().map_or(0, |s| s.get()).to_string();
+        Label[text = text.get_clone()]
+    }
+}
+
+fn counter() -> impl View {
+    view! {
+        state count = 0;
+        provide count {
+            Control {
+                Label[text = count.get().to_string()]
+                (label())
+            }
+        }
+    }
+}
+
+fn main() {
+    let _ = counter;
+}
@@ -0,0 +1,36 @@
+use godot::{
+    builtin::GString,
+    classes::{Area2D, Button, LineEdit, Node2D, Tree, TreeItem},
+    obj::Gd,
+};
+use lunar::{View, el, signals::*, view};
+
+fn builders() -> impl View {
+    (
+        el::<Button>().on_pressed(|| {}).on_toggled(|on: bool| {
+            let _ = on;
+        }),
+        el::<LineEdit>().on_text_changed(|text: GString| {
+            let _ = text;
+        }),
+        el::<Area2D>().on_body_entered(|body: Gd<Node2D>| {
+            let _ = body;
+        }),
+        el::<Tree>().on_item_edited(|| {}).on_button_clicked(
+            |item: Gd<TreeItem>, column: i64, id: i64, button: i64| {
+                let _ = (item, column, id, button);
+            },
+        ),
+    )
+}
+
+fn sugar() -> impl View {
+    view! {
+        state text = String::new();
+        LineEdit[@text_changed(t) = text.set(t.to_string())]
+    }
+}
+
+fn main() {
+    let _ = (builders, sugar);
+}
@@ -0,0 +1,9 @@
+//! Checks that the `view!` forms expand to code that compiles, and that the typed ones
+//! reject what godot wouldn't take.
+
+#[test]
+fn view_macro() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
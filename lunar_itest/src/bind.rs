@@ -0,0 +1,91 @@
+//! `name <- source` updates its node without rebuilding the views under the source.
+
+use std::{cell::Cell, rc::Rc};
+
+use godot::{
+    classes::{Label, Node},
+    obj::Gd,
+};
+use lunar::{State, on_rebuild, view};
+
+use crate::{Test, find};
+
+pub const TESTS: &[Test] = &[
+    ("bound_state_skips_rebuild", bound_state_skips_rebuild),
+    ("read_state_still_rebuilds", read_state_still_rebuilds),
+    ("bound_computed_skips_rebuild", bound_computed_skips_rebuild),
+];
+
+fn bound_state_skips_rebuild(root: Gd<Node>) {
+    let handle = Rc::new(Cell::new(None::<State<String>>));
+    let rebuilds = Rc::new(Cell::new(0));
+    let (h, r) = (handle.clone(), rebuilds.clone());
+    let app = lunar::start(root.clone(), move |_| {
+        let r = r.clone();
+        view! {
+            state text = String::from("a");
+            let _ = h.set(Some(text));
+            let r = r.clone();
+            Label[text <- text]
+            (on_rebuild(move || r.set(r.get() + 1)))
+        }
+    })
+    .into_guard();
+    let label = find::<Label>(&root);
+
+    handle.get().unwrap().set(String::from("b"));
+    app.run();
+    assert_eq!(label.get_text().to_string(), "b");
+    assert_eq!(rebuilds.get(), 0);
+}
+
+fn read_state_still_rebuilds(root: Gd<Node>) {
+    let handle = Rc::new(Cell::new(None::<State<String>>));
+    let rebuilds = Rc::new(Cell::new(0));
+    let (h, r) = (handle.clone(), rebuilds.clone());
+    let app = lunar::start(root.clone(), move |_| {
+        let r = r.clone();
+        view! {
+            state text = String::from("a");
+            let _ = h.set(Some(text));
+            let r = r.clone();
+            Label[text <- text]
+            Label[tooltip_text = text.get_clone()]
+            (on_rebuild(move || r.set(r.get() + 1)))
+        }
+    })
+    .into_guard();
+    let label = find::<Label>(&root);
+
+    handle.get().unwrap().set(String::from("b"));
+    app.run();
+    assert_eq!(label.get_text().to_string(), "b");
+    assert_eq!(label.get_tooltip_text().to_string(), "b");
+    assert_eq!(rebuilds.get(), 1);
+}
+
+fn bound_computed_skips_rebuild(root: Gd<Node>) {
+    let handle = Rc::new(Cell::new(None::<State<i32>>));
+    let rebuilds = Rc::new(Cell::new(0));
+    let (h, r) = (handle.clone(), rebuilds.clone());
+    let app = lunar::start(root.clone(), move |_| {
+        let r = r.clone();
+        view! {
+            state count = 1;
+            let _ = h.set(Some(count));
+            let r = r.clone();
+            computed doubled: String = (count.get() * 2).to_string();
+            let r = r.clone();
+            Label[text <- doubled]
+            (on_rebuild(move || r.set(r.get() + 1)))
+        }
+    })
+    .into_guard();
+    let label = find::<Label>(&root);
+    assert_eq!(label.get_text().to_string(), "2");
+
+    handle.get().unwrap().set(2);
+    app.run();
+    assert_eq!(label.get_text().to_string(), "4");
+    assert_eq!(rebuilds.get(), 0);
+}
@@ -0,0 +1,63 @@
+//! Tests that need a running engine. They are built into an extension which the
+//! `TestRunner` scene in `godot/` runs, see `tests/engine.rs`.
+
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use godot::{
+    classes::{INode, Node},
+    obj::{Base, Gd, Inherits, NewAlloc, WithBaseField},
+    prelude::{ExtensionLibrary, GodotClass, gdextension, godot_api, godot_print},
+};
+
+mod bind;
+mod lifecycle;
+mod prop;
+
+struct LunarItest;
+
+#[gdextension]
+unsafe impl ExtensionLibrary for LunarItest {}
+
+/// A test, given a fresh node inside the tree to start its app on.
+type Test = (&'static str, fn(Gd<Node>));
+
+/// The first node of class `T` below `root`.
+fn find<T: Inherits<Node>>(root: &Gd<Node>) -> Gd<T> {
+    root.find_children_ex("*")
+        .type_(&T::class_id().to_string())
+        .owned(false)
+        .done()
+        .get(0)
+        .expect("no such node")
+        .cast()
+}
+
+#[derive(GodotClass)]
+#[class(base=Node, init)]
+struct TestRunner {
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl INode for TestRunner {
+    fn ready(&mut self) {
+        let mut failed = 0;
+        for (name, test) in prop::TESTS
+            .iter()
+            .chain(lifecycle::TESTS)
+            .chain(bind::TESTS)
+        {
+            let root = Node::new_alloc();
+            self.base_mut().add_child(&root);
+            let ok = catch_unwind(AssertUnwindSafe(|| test(root.clone()))).is_ok();
+            if root.is_instance_valid() {
+                root.free();
+            }
+            godot_print!("test {name} ... {}", if ok { "ok" } else { "FAILED" });
+            failed += usize::from(!ok);
+        }
+        if let Some(mut tree) = self.base().get_tree() {
+            tree.quit_ex().exit_code(i32::from(failed > 0)).done();
+        }
+    }
+}
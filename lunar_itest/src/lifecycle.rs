@@ -0,0 +1,104 @@
+//! Stopping apps, from outside and from inside `run`.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use godot::{
+    classes::{Label, Node},
+    obj::Gd,
+};
+use lunar::{App, State, el, on_rebuild, on_teardown, stateful};
+
+use crate::{Test, find};
+
+pub const TESTS: &[Test] = &[
+    ("stop_tears_down", stop_tears_down),
+    (
+        "stop_inside_run_waits_for_run",
+        stop_inside_run_waits_for_run,
+    ),
+    ("stop_after_root_freed", stop_after_root_freed),
+];
+
+fn stop_tears_down(root: Gd<Node>) {
+    let torn_down = Rc::new(Cell::new(false));
+    let handle = Rc::new(Cell::new(None::<State<i32>>));
+    let (t, h) = (torn_down.clone(), handle.clone());
+    let app = lunar::start(root.clone(), move |_| {
+        stateful(
+            || 0,
+            move |state| {
+                h.set(Some(state));
+                let t = t.clone();
+                (el::<Label>(), on_teardown(move || t.set(true)))
+            },
+        )
+    });
+    let label = find::<Label>(&root);
+
+    app.stop();
+    assert!(torn_down.get());
+    assert!(!app.is_running());
+    assert!(!handle.get().unwrap().is_valid());
+    assert!(!label.is_instance_valid() || !label.is_inside_tree());
+    // stopping again does nothing
+    app.stop();
+}
+
+fn stop_inside_run_waits_for_run(root: Gd<Node>) {
+    let app = Rc::new(RefCell::new(None::<App>));
+    let torn_down = Rc::new(Cell::new(false));
+    let handle = Rc::new(Cell::new(None::<State<i32>>));
+    let (a, t, h) = (app.clone(), torn_down.clone(), handle.clone());
+    let started = lunar::start(root, move |_| {
+        stateful(
+            || 0,
+            move |state| {
+                h.set(Some(state));
+                let (a, t) = (a.clone(), t.clone());
+                (
+                    // like a signal fired by the rebuild that stops the app
+                    on_rebuild(move || {
+                        if let Some(app) = &*a.borrow() {
+                            app.stop();
+                        }
+                    }),
+                    on_teardown(move || t.set(true)),
+                )
+            },
+        )
+    });
+    *app.borrow_mut() = Some(started);
+
+    handle.get().unwrap().set(1);
+    let app = app.borrow();
+    let app = app.as_ref().unwrap();
+    app.run();
+    assert!(torn_down.get());
+    assert!(!app.is_running());
+}
+
+fn stop_after_root_freed(root: Gd<Node>) {
+    let torn_down = Rc::new(Cell::new(false));
+    let handle = Rc::new(Cell::new(None::<State<i32>>));
+    let (t, h) = (torn_down.clone(), handle.clone());
+    let app = lunar::start(root.clone(), move |_| {
+        stateful(
+            || 0,
+            move |state| {
+                h.set(Some(state));
+                let t = t.clone();
+                (el::<Label>(), on_teardown(move || t.set(true)))
+            },
+        )
+    });
+
+    // the view nodes go with the root, everything else is still cleaned up
+    root.free();
+    app.stop();
+    assert!(torn_down.get());
+    assert!(!app.is_running());
+    assert!(!handle.get().unwrap().is_valid());
+}
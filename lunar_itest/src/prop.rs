@@ -0,0 +1,106 @@
+//! Rebuilds put back a controlled property the user changed, and `name? = None` puts
+//! back the value from before.
+
+use std::{cell::Cell, rc::Rc};
+
+use godot::{
+    builtin::Color,
+    classes::{Label, LineEdit, Node},
+    obj::Gd,
+};
+use lunar::{State, view};
+
+use crate::{Test, find};
+
+pub const TESTS: &[Test] = &[
+    ("prop_resets_user_edit", prop_resets_user_edit),
+    ("attr_resets_user_edit", attr_resets_user_edit),
+    (
+        "theme_override_resets_user_edit",
+        theme_override_resets_user_edit,
+    ),
+    ("prop_opt_restores_on_none", prop_opt_restores_on_none),
+];
+
+fn prop_resets_user_edit(root: Gd<Node>) {
+    let handle = Rc::new(Cell::new(None::<State<String>>));
+    let h = handle.clone();
+    let app = lunar::start(root.clone(), move |_| {
+        view! {
+            state text = String::from("declared");
+            let _ = h.set(Some(text));
+            LineEdit[text = text.get_clone()]
+        }
+    })
+    .into_guard();
+    let mut edit = find::<LineEdit>(&root);
+    assert_eq!(edit.get_text().to_string(), "declared");
+
+    // what typing into the field does
+    edit.set_text("typed");
+    handle.get().unwrap().notify();
+    app.run();
+    assert_eq!(edit.get_text().to_string(), "declared");
+}
+
+fn attr_resets_user_edit(root: Gd<Node>) {
+    let handle = Rc::new(Cell::new(None::<State<String>>));
+    let h = handle.clone();
+    let app = lunar::start(root.clone(), move |_| {
+        view! {
+            state text = String::from("declared");
+            let _ = h.set(Some(text));
+            LineEdit[attr text = text.get_clone()]
+        }
+    })
+    .into_guard();
+    let mut edit = find::<LineEdit>(&root);
+
+    edit.set_text("typed");
+    handle.get().unwrap().notify();
+    app.run();
+    assert_eq!(edit.get_text().to_string(), "declared");
+}
+
+fn theme_override_resets_user_edit(root: Gd<Node>) {
+    let handle = Rc::new(Cell::new(None::<State<()>>));
+    let h = handle.clone();
+    let app = lunar::start(root.clone(), move |_| {
+        view! {
+            state unit = ();
+            let _ = h.set(Some(unit));
+            Label[#color:font_color = Color::RED]
+        }
+    })
+    .into_guard();
+    let mut label = find::<Label>(&root);
+
+    label.add_theme_color_override("font_color", Color::BLUE);
+    handle.get().unwrap().notify();
+    app.run();
+    assert_eq!(label.get_theme_color("font_color"), Color::RED);
+}
+
+fn prop_opt_restores_on_none(root: Gd<Node>) {
+    let handle = Rc::new(Cell::new(None::<State<Option<String>>>));
+    let h = handle.clone();
+    let app = lunar::start(root.clone(), move |_| {
+        view! {
+            state hint = Some(String::from("declared"));
+            let _ = h.set(Some(hint));
+            LineEdit[placeholder_text? = hint.get_clone()]
+        }
+    })
+    .into_guard();
+    let edit = find::<LineEdit>(&root);
+    assert_eq!(edit.get_placeholder().to_string(), "declared");
+
+    let hint = handle.get().unwrap();
+    hint.set(None);
+    app.run();
+    assert_eq!(edit.get_placeholder().to_string(), "");
+
+    hint.set(Some(String::from("again")));
+    app.run();
+    assert_eq!(edit.get_placeholder().to_string(), "again");
+}
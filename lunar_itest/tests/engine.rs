@@ -0,0 +1,30 @@
+//! Runs the tests in `src/` inside godot, if `GODOT4_BIN` points at a godot 4.5 binary.
+
+use std::process::Command;
+
+#[test]
+fn engine() {
+    let Ok(godot) = std::env::var("GODOT4_BIN") else {
+        eprintln!("GODOT4_BIN is not set, skipping the engine tests");
+        return;
+    };
+    // the test build of this crate doesn't produce the extension
+    let build = Command::new(env!("CARGO"))
+        .args(["build", "-p", "lunar_itest"])
+        .status()
+        .expect("failed to start cargo");
+    assert!(build.success());
+
+    let project = concat!(env!("CARGO_MANIFEST_DIR"), "/godot");
+    // the extension is only loaded once the project was imported
+    let import = Command::new(&godot)
+        .args(["--headless", "--import", "--path", project])
+        .status()
+        .expect("failed to start godot");
+    assert!(import.success());
+    let run = Command::new(&godot)
+        .args(["--headless", "--path", project])
+        .status()
+        .expect("failed to start godot");
+    assert!(run.success(), "engine tests failed");
+}
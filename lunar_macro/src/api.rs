@@ -0,0 +1,288 @@
+//! The classes and properties from godot's `extension_api.json`, the same metadata gdext
+//! generates its bindings from.
+
+use std::{collections::HashMap, sync::OnceLock};
+
+use heck::{ToPascalCase, ToSnakeCase};
+use serde_json::Value;
+
+pub struct Api {
+    pub classes: HashMap<String, Class>,
+    /// gdext's names for the classes, e.g. `CpuParticles2D` for `CPUParticles2D`.
+    rust_names: HashMap<String, String>,
+}
+
+pub struct Class {
+    pub inherits: Option<String>,
+    pub properties: Vec<Property>,
+    /// Non-virtual instance methods, with their arguments.
+    pub methods: HashMap<String, Vec<Arg>>,
+    pub signals: Vec<Signal>,
+}
+
+pub struct Signal {
+    pub name: String,
+    pub args: Vec<Arg>,
+}
+
+pub struct Property {
+    pub name: String,
+    pub setter: String,
+    /// Set for properties sharing one setter, like `offset_left` with `set_offset(SIDE_LEFT, _)`.
+    pub index: Option<i64>,
+}
+
+pub struct Arg {
+    pub typ: String,
+    pub meta: Option<String>,
+    pub default: bool,
+}
+
+/// What `name = value` on an element of some class turns into.
+pub enum Lookup<'a> {
+    /// Not an engine class, e.g. one registered by the user.
+    UnknownClass,
+    Missing,
+    /// The property exists but has no plain one argument setter.
+    Untyped,
+    Setter(&'a str),
+}
+
+/// Classes gdext leaves out by default, like the ones behind its `experimental-godot-api`
+/// feature, so the generated props don't mention them.
+const SKIPPED: &[&str] = &[
+    "AudioSample",
+    "AudioSamplePlayback",
+    "Compositor",
+    "CompositorEffect",
+    "GraphEdit",
+    "GraphElement",
+    "GraphFrame",
+    "GraphNode",
+    "NavigationAgent2D",
+    "NavigationAgent3D",
+    "NavigationLink2D",
+    "NavigationLink3D",
+    "NavigationMesh",
+    "NavigationMeshSourceGeometryData2D",
+    "NavigationMeshSourceGeometryData3D",
+    "NavigationObstacle2D",
+    "NavigationObstacle3D",
+    "NavigationPathQueryParameters2D",
+    "NavigationPathQueryParameters3D",
+    "NavigationPathQueryResult2D",
+    "NavigationPathQueryResult3D",
+    "NavigationPolygon",
+    "NavigationRegion2D",
+    "NavigationRegion3D",
+    "NavigationServer2D",
+    "NavigationServer3D",
+    "Parallax2D",
+    "SkeletonModification2D",
+    "SkeletonModification2DCCDIK",
+    "SkeletonModification2DFABRIK",
+    "SkeletonModification2DJiggle",
+    "SkeletonModification2DLookAt",
+    "SkeletonModification2DPhysicalBones",
+    "SkeletonModification2DStackHolder",
+    "SkeletonModification2DTwoBoneIK",
+    "SkeletonModificationStack2D",
+    "StreamPeerGZIP",
+    "XRBodyModifier3D",
+    "XRBodyTracker",
+    "XRFaceModifier3D",
+    "XRFaceTracker",
+    "JavaClass",
+    "JavaClassWrapper",
+    "JavaObject",
+    "JNISingleton",
+    "JavaScriptBridge",
+    "JavaScriptObject",
+    "Thread",
+    "Mutex",
+    "Semaphore",
+];
+
+/// Methods gdext doesn't generate, or generates with an enum where the json has an int.
+const SKIPPED_METHODS: &[(&str, &str)] = &[
+    ("OpenXRHand", "set_hand_skeleton"),
+    ("SkeletonIK3D", "set_interpolation"),
+    ("VisualShaderNodeComment", "set_title"),
+    ("VisualShaderNodeComment", "set_description"),
+    ("VisualShader", "set_graph_offset"),
+    ("CharFXTransform", "set_glyph_flags"),
+    ("ProgressBar", "set_fill_mode"),
+    ("TextEdit", "set_search_flags"),
+    ("TextureProgressBar", "set_fill_mode"),
+    ("Tree", "set_drop_mode_flags"),
+];
+
+pub fn api() -> &'static Api {
+    static API: OnceLock<Api> = OnceLock::new();
+    API.get_or_init(|| {
+        // the same api as godot's `api-*` feature picks, since host dependencies share features
+        let json = godot_bindings::load_gdextension_json(&mut godot_bindings::StopWatch::start());
+        let json: Value = serde_json::from_str(&json).expect("invalid extension_api.json");
+        let classes = json["classes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|class| (str(&class["name"]).to_string(), Class::from_json(class)))
+            .collect::<HashMap<_, _>>();
+        let rust_names = classes
+            .keys()
+            .map(|class| (pascal_name(class), class.clone()))
+            .collect();
+        Api {
+            classes,
+            rust_names,
+        }
+    })
+}
+
+fn special_snake_name(class: &str) -> Option<&'static str> {
+    match class {
+        "JSONRPC" => Some("json_rpc"),
+        "OpenXRAPIExtension" => Some("open_xr_api_extension"),
+        "OpenXRIPBinding" => Some("open_xr_ip_binding"),
+        _ => None,
+    }
+}
+
+/// gdext's module name for a class, e.g. `texture_2d` for `Texture2D`.
+pub fn module_name(class: &str) -> String {
+    match special_snake_name(class) {
+        Some(name) => name.to_string(),
+        None => class
+            .replace("1D", "_1d")
+            .replace("2D", "_2d")
+            .replace("3D", "_3d")
+            .replace("GDNative", "Gdnative")
+            .replace("GDExtension", "Gdextension")
+            .replace("GDScript", "Gdscript")
+            .replace("VSync", "Vsync")
+            .replace("SDFGIY", "SdfgiY")
+            .replace("ENet", "Enet")
+            .to_snake_case(),
+    }
+}
+
+/// gdext's name for a class or enum, e.g. `CpuParticles2D` for `CPUParticles2D`.
+pub fn pascal_name(name: &str) -> String {
+    if let Some(name) = special_snake_name(name) {
+        return name.to_pascal_case();
+    }
+    name.to_pascal_case()
+        .replace("GdExtension", "GDExtension")
+        .replace("GdNative", "GDNative")
+        .replace("GdScript", "GDScript")
+        .replace("Vsync", "VSync")
+        .replace("Sdfgiy", "SdfgiY")
+}
+
+fn str(v: &Value) -> &str {
+    v.as_str().unwrap_or_default()
+}
+
+fn args(method: &Value) -> Vec<Arg> {
+    method["arguments"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|a| Arg {
+            typ: str(&a["type"]).to_string(),
+            meta: a["meta"].as_str().map(str::to_string),
+            default: a.get("default_value").is_some(),
+        })
+        .collect()
+}
+
+impl Class {
+    fn from_json(class: &Value) -> Self {
+        let properties = class["properties"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|p| Property {
+                name: str(&p["name"]).to_string(),
+                setter: str(&p["setter"]).to_string(),
+                index: p["index"].as_i64(),
+            })
+            .collect();
+        let methods = class["methods"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|m| m["is_virtual"] != true && m["is_static"] != true)
+            .map(|m| (str(&m["name"]).to_string(), args(m)))
+            .collect();
+        let signals = class["signals"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|s| Signal {
+                name: str(&s["name"]).to_string(),
+                args: args(s),
+            })
+            .collect();
+        Class {
+            inherits: class["inherits"].as_str().map(str::to_string),
+            properties,
+            methods,
+            signals,
+        }
+    }
+}
+
+impl Api {
+    /// The class and its bases, starting with the class itself.
+    pub fn chain<'a>(&'a self, class: &str) -> impl Iterator<Item = (&'a str, &'a Class)> {
+        std::iter::successors(self.classes.get_key_value(class), |(_, c)| {
+            self.classes.get_key_value(c.inherits.as_deref()?)
+        })
+        .map(|(name, c)| (name.as_str(), c))
+    }
+
+    pub fn is_skipped(&self, class: &str) -> bool {
+        self.chain(class)
+            .any(|(name, _)| SKIPPED.contains(&name) || name.starts_with("OpenXR"))
+    }
+
+    pub fn method(&self, class: &str, name: &str) -> Option<&Vec<Arg>> {
+        let (class, args) = self
+            .chain(class)
+            .find_map(|(class, c)| Some((class, c.methods.get(name)?)))?;
+        (!SKIPPED_METHODS.contains(&(class, name))).then_some(args)
+    }
+
+    /// The one argument a property's setter takes, if it's a plain `set_x(value)`.
+    pub fn setter_arg(&self, class: &str, property: &Property) -> Option<&Arg> {
+        if property.index.is_some()
+            || property.setter.is_empty()
+            || property.setter.starts_with('_')
+        {
+            return None;
+        }
+        match self.method(class, &property.setter)?.as_slice() {
+            [arg, rest @ ..] if rest.iter().all(|a| a.default) => Some(arg),
+            _ => None,
+        }
+    }
+
+    /// Looks up a property on a class by its gdext name, e.g. `Label`.
+    pub fn lookup(&self, class: &str, name: &str) -> Lookup<'_> {
+        let Some(class) = self.rust_names.get(class) else {
+            return Lookup::UnknownClass;
+        };
+        let Some(property) = self
+            .chain(class)
+            .find_map(|(_, c)| c.properties.iter().find(|p| p.name == name))
+        else {
+            return Lookup::Missing;
+        };
+        match self.setter_arg(class, property) {
+            Some(_) => Lookup::Setter(&property.setter),
+            None => Lookup::Untyped,
+        }
+    }
+}
@@ -1,3 +1,6 @@
+mod api;
+mod props;
+mod signals;
 mod util;
 mod view;
 
@@ -14,3 +17,15 @@ pub fn view(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
     b.gen_rust().into()
 }
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __element_props(_item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    props::gen_props().into()
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __element_signals(_item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    signals::gen_signals().into()
+}
@@ -0,0 +1,159 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::api::{Api, Arg, api, module_name, pascal_name};
+
+/// How a setter wants the stored value.
+pub enum Pass {
+    Copy,
+    Ref,
+    Object,
+}
+
+/// The Rust type gdext uses for a setter argument, for the ones props support.
+pub fn rust_type(api: &Api, arg: &Arg) -> Option<(TokenStream, Pass)> {
+    let copy = |ty| Some((ty, Pass::Copy));
+    match (arg.typ.as_str(), arg.meta.as_deref()) {
+        ("bool", _) => copy(quote! { bool }),
+        ("int", None | Some("int64")) => copy(quote! { i64 }),
+        ("int", Some("int32")) => copy(quote! { i32 }),
+        ("int", Some("int16")) => copy(quote! { i16 }),
+        ("int", Some("int8")) => copy(quote! { i8 }),
+        ("int", Some("uint64")) => copy(quote! { u64 }),
+        ("int", Some("uint32")) => copy(quote! { u32 }),
+        ("int", Some("uint16")) => copy(quote! { u16 }),
+        ("int", Some("uint8")) => copy(quote! { u8 }),
+        ("float", Some("float")) => copy(quote! { f32 }),
+        ("float", None | Some("double")) => copy(quote! { f64 }),
+        ("AABB", _) => copy(quote! { ::godot::builtin::Aabb }),
+        ("RID", _) => copy(quote! { ::godot::builtin::Rid }),
+        (
+            typ @ ("Vector2" | "Vector2i" | "Vector3" | "Vector3i" | "Vector4" | "Vector4i"
+            | "Rect2" | "Rect2i" | "Color" | "Transform2D" | "Transform3D" | "Basis"
+            | "Quaternion" | "Plane" | "Projection"),
+            _,
+        ) => {
+            let typ = Ident::new(typ, Span::call_site());
+            copy(quote! { ::godot::builtin::#typ })
+        }
+        ("String", _) => Some((quote! { ::godot::builtin::GString }, Pass::Ref)),
+        (typ @ ("StringName" | "NodePath" | "Callable"), _) => {
+            let typ = Ident::new(typ, Span::call_site());
+            Some((quote! { ::godot::builtin::#typ }, Pass::Ref))
+        }
+        (typ, _) if typ.starts_with("Packed") && typ.ends_with("Array") => {
+            let typ = Ident::new(typ, Span::call_site());
+            Some((quote! { ::godot::builtin::#typ }, Pass::Ref))
+        }
+        (typ, _) if typ.starts_with("enum::") || typ.starts_with("bitfield::") => {
+            let (_, path) = typ.split_once("::")?;
+            match path.split_once('.') {
+                Some((class, name)) => {
+                    if !api.classes.contains_key(class) || api.is_skipped(class) {
+                        return None;
+                    }
+                    let module = Ident::new(&module_name(class), Span::call_site());
+                    let name = Ident::new(&pascal_name(name), Span::call_site());
+                    copy(quote! { ::godot::classes::#module::#name })
+                }
+                None if matches!(path, "Corner" | "EulerOrder" | "Side") => {
+                    let name = Ident::new(path, Span::call_site());
+                    copy(quote! { ::godot::builtin::#name })
+                }
+                None => {
+                    let name = Ident::new(&pascal_name(path), Span::call_site());
+                    copy(quote! { ::godot::global::#name })
+                }
+            }
+        }
+        (class, _) if api.classes.contains_key(class) && !api.is_skipped(class) => {
+            let class = Ident::new(&pascal_name(class), Span::call_site());
+            Some((
+                quote! { Option<::godot::obj::Gd<::godot::classes::#class>> },
+                Pass::Object,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// One `<Class>Props` trait per node class, with a builder method per property that has a
+/// plain setter, e.g. `LabelProps::text`.
+pub fn gen_props() -> TokenStream {
+    let api = api();
+    let mut classes = api
+        .classes
+        .keys()
+        .filter(|class| api.chain(class).any(|(name, _)| name == "Node") && !api.is_skipped(class))
+        .collect::<Vec<_>>();
+    classes.sort();
+
+    let mut out = TokenStream::new();
+    for class in classes {
+        let class_ident = Ident::new(&pascal_name(class), Span::call_site());
+        let declared = &api.classes[class.as_str()].properties;
+        let methods = declared.iter().filter_map(|property| {
+            // a base declaring the same name would make the method ambiguous
+            let inherited = api
+                .chain(class)
+                .skip(1)
+                .any(|(_, c)| c.properties.iter().any(|p| p.name == property.name));
+            if inherited {
+                return None;
+            }
+            let name = syn::parse_str::<Ident>(&property.name).ok()?;
+            let setter = syn::parse_str::<Ident>(&property.setter).ok()?;
+            let (typ, pass) = rust_type(api, api.setter_arg(class, property)?)?;
+            let (param, arg) = match pass {
+                Pass::Copy => (quote! { #typ }, quote! { *value }),
+                Pass::Ref => (quote! { impl ::godot::meta::AsArg<#typ> }, quote! { value }),
+                Pass::Object => (
+                    quote! { impl ::godot::meta::AsArg<#typ> },
+                    quote! { value.as_ref() },
+                ),
+            };
+            let value = match pass {
+                Pass::Copy => quote! {},
+                Pass::Ref | Pass::Object => quote! { ::godot::meta::arg_into_owned!(infer value); },
+            };
+            let prop_name = &property.name;
+            let doc = format!("Sets `{class}.{prop_name}` through `{setter}`.");
+            Some(quote! {
+                #[doc = #doc]
+                fn #name(self, value: #param) -> Prop<N, #typ, fn(&mut Gd<N>, &#typ), Self, false> {
+                    #value
+                    Prop {
+                        inner: self,
+                        name: #prop_name,
+                        value,
+                        setter: |node, value| node.upcast_mut::<::godot::classes::#class_ident>().#setter(#arg),
+                        _p: PhantomData,
+                    }
+                }
+            })
+        });
+        let methods = methods.collect::<Vec<_>>();
+        if methods.is_empty() {
+            continue;
+        }
+        let trait_name = format_ident!("{class_ident}Props");
+        let doc = format!("Typed builder methods for the properties `{class}` declares.");
+        out.extend(quote! {
+            #[doc = #doc]
+            pub trait #trait_name<N>: ElementView<N> + Sized
+            where
+                N: Inherits<::godot::classes::#class_ident> + Inherits<Node>,
+            {
+                #(#methods)*
+            }
+            impl<N, V> #trait_name<N> for V
+            where
+                V: ElementView<N>,
+                N: Inherits<::godot::classes::#class_ident> + Inherits<Node>,
+            {
+            }
+        });
+    }
+    out
+}
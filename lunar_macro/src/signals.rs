@@ -0,0 +1,119 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::{
+    api::{Api, Arg, api, pascal_name},
+    props::{Pass, rust_type},
+};
+
+/// The type gdext's typed signals pass an argument as, which unlike setters takes
+/// objects as `Gd<T>`.
+fn param_type(api: &Api, arg: &Arg) -> Option<TokenStream> {
+    match arg.typ.as_str() {
+        "Variant" => return Some(quote! { ::godot::builtin::Variant }),
+        "Dictionary" => return Some(quote! { ::godot::builtin::VarDictionary }),
+        "Array" => return Some(quote! { ::godot::builtin::VarArray }),
+        _ => {}
+    }
+    if let Some(elem) = arg.typ.strip_prefix("typedarray::") {
+        let elem = Arg {
+            typ: elem.to_string(),
+            meta: None,
+            default: false,
+        };
+        let elem = param_type(api, &elem)?;
+        return Some(quote! { ::godot::builtin::Array<#elem> });
+    }
+    match rust_type(api, arg)? {
+        (_, Pass::Object) => {
+            let class = Ident::new(&pascal_name(&arg.typ), Span::call_site());
+            Some(quote! { ::godot::obj::Gd<::godot::classes::#class> })
+        }
+        (typ, _) => Some(typ),
+    }
+}
+
+/// One `<Class>Signals` trait per node class, with an `on_<signal>` method per signal it
+/// declares, e.g. `LineEditSignals::on_text_changed`.
+pub fn gen_signals() -> TokenStream {
+    let api = api();
+    let mut classes = api
+        .classes
+        .keys()
+        .filter(|class| api.chain(class).any(|(name, _)| name == "Node") && !api.is_skipped(class))
+        .collect::<Vec<_>>();
+    classes.sort();
+
+    let mut out = TokenStream::new();
+    for class in classes {
+        let class_ident = Ident::new(&pascal_name(class), Span::call_site());
+        let methods = api.classes[class.as_str()]
+            .signals
+            .iter()
+            .filter_map(|signal| {
+                let name = syn::parse_str::<Ident>(&signal.name).ok()?;
+                let method = format_ident!("on_{name}");
+                let types = signal
+                    .args
+                    .iter()
+                    .map(|arg| param_type(api, arg))
+                    .collect::<Option<Vec<_>>>()?;
+                let args = (0..types.len())
+                    .map(|i| format_ident!("arg{i}"))
+                    .collect::<Vec<_>>();
+                let doc = format!("Calls `cb` on `{class}.{name}`.");
+                Some(quote! {
+                    #[doc = #doc]
+                    fn #method<F>(
+                        self,
+                        cb: F,
+                    ) -> OnTyped<
+                        N,
+                        (#(#types,)*),
+                        fn(&Gd<N>, TypedCallback<(#(#types,)*)>) -> ConnectHandle,
+                        impl Fn((#(#types,)*)) + 'static,
+                        Self,
+                    >
+                    where
+                        F: Fn(#(#types),*) + 'static,
+                    {
+                        OnTyped {
+                            inner: self,
+                            connect: |node, cb| {
+                                node.clone()
+                                    .upcast::<::godot::classes::#class_ident>()
+                                    .signals()
+                                    .#name()
+                                    .connect(move |#(#args),*| cb.call((#(#args,)*)))
+                            },
+                            cb: Rc::new(move |(#(#args,)*)| cb(#(#args),*)),
+                            _p: PhantomData,
+                        }
+                    }
+                })
+            });
+        let methods = methods.collect::<Vec<_>>();
+        if methods.is_empty() {
+            continue;
+        }
+        let trait_name = format_ident!("{class_ident}Signals");
+        let doc = format!("Typed handlers for the signals `{class}` declares.");
+        out.extend(quote! {
+            #[doc = #doc]
+            pub trait #trait_name<N>: ElementView<N> + Sized
+            where
+                N: Inherits<::godot::classes::#class_ident> + Inherits<Node>,
+            {
+                #(#methods)*
+            }
+            impl<N, V> #trait_name<N> for V
+            where
+                V: ElementView<N>,
+                N: Inherits<::godot::classes::#class_ident> + Inherits<Node>,
+            {
+            }
+        });
+    }
+    out
+}
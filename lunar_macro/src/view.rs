@@ -1,20 +1,32 @@
 use either::Either::{self, Left, Right};
 use heck::ToUpperCamelCase;
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{quote, quote_spanned};
 use syn::{
-    AngleBracketedGenericArguments, Expr, Ident, Pat, Token, Type, braced, bracketed,
+    AngleBracketedGenericArguments, Block, Expr, Ident, Pat, Token, Type, braced, bracketed,
     parenthesized, parse::Parse, parse_quote, punctuated::Punctuated, token,
 };
 
-use crate::util::take_until_semicolon;
+use crate::{
+    api::{Lookup, api},
+    util::take_until_semicolon,
+};
 
 mod kw {
     syn::custom_keyword!(when);
     syn::custom_keyword!(state);
     syn::custom_keyword!(quiet);
+    syn::custom_keyword!(eq);
+    syn::custom_keyword!(history);
+    syn::custom_keyword!(persisted);
+    syn::custom_keyword!(reducer);
     syn::custom_keyword!(build);
+    syn::custom_keyword!(attr);
     syn::custom_keyword!(memo);
+    syn::custom_keyword!(task);
+    syn::custom_keyword!(computed);
+    syn::custom_keyword!(provide);
+    syn::custom_keyword!(store);
 }
 
 pub struct ViewBody {
@@ -52,6 +64,22 @@ pub enum ViewType {
     State {
         kw: kw::state,
         quiet: Option<kw::quiet>,
+        eq: Option<kw::eq>,
+        kind: StateKind,
+        name: Ident,
+        typ: Type,
+        init: TokenStream,
+        body: ViewBody,
+    },
+    Computed {
+        kw: kw::computed,
+        name: Ident,
+        typ: Type,
+        init: TokenStream,
+        body: ViewBody,
+    },
+    Store {
+        kw: kw::store,
         name: Ident,
         typ: Type,
         init: TokenStream,
@@ -67,6 +95,15 @@ pub enum ViewType {
         expr: Expr,
         body: ViewBody,
     },
+    Provide {
+        kw: kw::provide,
+        expr: Expr,
+        body: ViewBody,
+    },
+    Task {
+        kw: kw::task,
+        block: Block,
+    },
     Let {
         pat: Pat,
         typ: Type,
@@ -75,22 +112,83 @@ pub enum ViewType {
     },
 }
 
+/// The `state` forms that build something other than a plain `State`.
+pub enum StateKind {
+    Plain,
+    History(kw::history),
+    Persisted(kw::persisted, TokenStream),
+    Reducer(kw::reducer, TokenStream),
+}
+
 pub enum ElemModifier {
-    Attr(Ident, Expr, Option<kw::build>),
+    /// `name = value`, set through the property's typed setter when godot's metadata has
+    /// one, and by name otherwise.
+    Prop(Ident, Expr, Option<kw::build>, Option<Ident>),
+    /// `attr name = value`, set by name through a `Variant`.
+    Attr(Ident, Expr, Option<kw::build>, kw::attr),
+    /// `name <- source`, kept in sync with a `State` or `Computed` through a watch.
+    AttrBind(Ident, Expr),
+    /// `name <=> state`, optionally followed by `@signal`.
+    Bind(Ident, Expr, Ident),
     OnSignal(Ident, Expr),
+    /// `@name(args) = body`, connected through the typed signal.
+    OnTyped(Ident, Punctuated<Pat, Token![,]>, Expr),
+    /// `name? = value`, where `None` puts back the value from before. Typed like `Prop`
+    /// unless written `attr name? = value`.
+    PropOpt(Ident, Expr, Option<kw::attr>, Option<Ident>),
     ThemeOverride {
         typ: Ident,
         name: Ident,
+        opt: Option<Token![?]>,
         value: Expr,
     },
     NodeRef(Expr),
 }
 
+/// The typed setter for `name = value` on an element, or `None` to set it by name.
+fn prop_setter(class: &Ident, name: &Ident) -> syn::Result<Option<Ident>> {
+    match api().lookup(&class.to_string(), &name.to_string()) {
+        Lookup::Setter(setter) => Ok(Some(Ident::new(setter, name.span()))),
+        Lookup::UnknownClass | Lookup::Untyped => Ok(None),
+        Lookup::Missing => Err(syn::Error::new(
+            name.span(),
+            format!(
+                "`{class}` has no property `{name}`, use `attr {name} = ...` for one godot doesn't know about"
+            ),
+        )),
+    }
+}
+
+/// The signal a property is usually changed by, for `name <=> state` without `@signal`.
+fn default_bind_signal(name: &Ident) -> syn::Result<Ident> {
+    let signal = match name.to_string().as_str() {
+        "text" => "text_changed",
+        "value" => "value_changed",
+        "button_pressed" => "toggled",
+        "selected" => "item_selected",
+        _ => {
+            return Err(syn::Error::new(
+                name.span(),
+                format!("no default signal for `{name}`, name one with `{name} <=> state @signal`"),
+            ));
+        }
+    };
+    Ok(Ident::new(signal, name.span()))
+}
+
 impl Parse for ElemModifier {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         if input.peek(Token![@]) {
             input.parse::<Token![@]>()?;
             let name = input.parse()?;
+            if input.peek(token::Paren) {
+                let inner;
+                parenthesized!(inner in input);
+                let args = inner.parse_terminated(Pat::parse_single, Token![,])?;
+                input.parse::<Token![=]>()?;
+                let body = input.parse()?;
+                return Ok(ElemModifier::OnTyped(name, args, body));
+            }
             input.parse::<Token![=]>()?;
             let value = input.parse()?;
             Ok(ElemModifier::OnSignal(name, value))
@@ -99,9 +197,15 @@ impl Parse for ElemModifier {
             let typ = input.parse()?;
             input.parse::<Token![:]>()?;
             let name = input.parse()?;
+            let opt = input.parse()?;
             input.parse::<Token![=]>()?;
             let value = input.parse()?;
-            Ok(ElemModifier::ThemeOverride { typ, name, value })
+            Ok(ElemModifier::ThemeOverride {
+                typ,
+                name,
+                opt,
+                value,
+            })
         } else if input.peek(Token![ref]) {
             input.parse::<Token![ref]>()?;
             let inner;
@@ -114,10 +218,39 @@ impl Parse for ElemModifier {
             } else {
                 None
             };
-            let name = input.parse()?;
+            let attr = if input.peek(kw::attr) && input.peek2(syn::Ident) {
+                Some(input.parse::<kw::attr>()?)
+            } else {
+                None
+            };
+            let name: Ident = input.parse()?;
+            if build.is_none() && attr.is_none() && input.peek(Token![<=]) {
+                input.parse::<Token![<=]>()?;
+                input.parse::<Token![>]>()?;
+                let state = input.parse()?;
+                let signal = if input.peek(Token![@]) {
+                    input.parse::<Token![@]>()?;
+                    input.parse()?
+                } else {
+                    default_bind_signal(&name)?
+                };
+                return Ok(ElemModifier::Bind(name, state, signal));
+            }
+            if build.is_none() && attr.is_none() && input.peek(Token![<-]) {
+                input.parse::<Token![<-]>()?;
+                return Ok(ElemModifier::AttrBind(name, input.parse()?));
+            }
+            if build.is_none() && input.peek(Token![?]) {
+                input.parse::<Token![?]>()?;
+                input.parse::<Token![=]>()?;
+                return Ok(ElemModifier::PropOpt(name, input.parse()?, attr, None));
+            }
             input.parse::<Token![=]>()?;
-            let value = input.parse()?;
-            Ok(ElemModifier::Attr(name, value, build))
+            let value: Expr = input.parse()?;
+            Ok(match attr {
+                Some(attr) => ElemModifier::Attr(name, value, build, attr),
+                None => ElemModifier::Prop(name, value, build, None),
+            })
         }
     }
 }
@@ -185,11 +318,54 @@ impl Parse for ViewType {
             Ok(ViewType::Dyn(body))
         } else if input.peek(kw::state) {
             let kw = input.parse::<kw::state>()?;
-            let quiet = if input.peek(kw::quiet) {
-                Some(input.parse::<kw::quiet>()?)
-            } else {
-                None
+            let mut quiet = None;
+            let mut eq = None;
+            let mut kind = StateKind::Plain;
+            // in any order, as long as a name follows
+            loop {
+                if quiet.is_none() && input.peek(kw::quiet) && input.peek2(Ident) {
+                    quiet = Some(input.parse::<kw::quiet>()?);
+                } else if eq.is_none() && input.peek(kw::eq) && input.peek2(Ident) {
+                    eq = Some(input.parse::<kw::eq>()?);
+                } else if matches!(kind, StateKind::Plain)
+                    && input.peek(kw::history)
+                    && input.peek2(Ident)
+                {
+                    kind = StateKind::History(input.parse()?);
+                } else if matches!(kind, StateKind::Plain)
+                    && input.peek(kw::persisted)
+                    && input.peek2(token::Paren)
+                {
+                    let kw = input.parse()?;
+                    let inner;
+                    parenthesized!(inner in input);
+                    kind = StateKind::Persisted(kw, inner.parse()?);
+                } else if matches!(kind, StateKind::Plain)
+                    && input.peek(kw::reducer)
+                    && input.peek2(token::Paren)
+                {
+                    let kw = input.parse()?;
+                    let inner;
+                    parenthesized!(inner in input);
+                    kind = StateKind::Reducer(kw, inner.parse()?);
+                } else {
+                    break;
+                }
+            }
+            let kind_span = match &kind {
+                StateKind::Plain => None,
+                StateKind::History(kw) => Some(kw.span),
+                StateKind::Persisted(kw, _) => Some(kw.span),
+                StateKind::Reducer(kw, _) => Some(kw.span),
             };
+            if let Some(span) = kind_span
+                && (quiet.is_some() || eq.is_some())
+            {
+                return Err(syn::Error::new(
+                    span,
+                    "can't be combined with `quiet` or `eq`",
+                ));
+            }
             let name = input.parse()?;
             let typ = if input.peek(Token![:]) {
                 input.parse::<Token![:]>()?;
@@ -203,6 +379,46 @@ impl Parse for ViewType {
             Ok(ViewType::State {
                 kw,
                 quiet,
+                eq,
+                kind,
+                name,
+                typ,
+                init,
+                body,
+            })
+        } else if input.peek(kw::computed) {
+            let kw = input.parse::<kw::computed>()?;
+            let name = input.parse()?;
+            let typ = if input.peek(Token![:]) {
+                input.parse::<Token![:]>()?;
+                input.parse()?
+            } else {
+                parse_quote! { _ }
+            };
+            input.parse::<Token![=]>()?;
+            let init = take_until_semicolon(input)?;
+            let body = input.parse()?;
+            Ok(ViewType::Computed {
+                kw,
+                name,
+                typ,
+                init,
+                body,
+            })
+        } else if input.peek(kw::store) {
+            let kw = input.parse::<kw::store>()?;
+            let name = input.parse()?;
+            let typ = if input.peek(Token![:]) {
+                input.parse::<Token![:]>()?;
+                input.parse()?
+            } else {
+                parse_quote! { _ }
+            };
+            input.parse::<Token![=]>()?;
+            let init = take_until_semicolon(input)?;
+            let body = input.parse()?;
+            Ok(ViewType::Store {
+                kw,
                 name,
                 typ,
                 init,
@@ -240,6 +456,17 @@ impl Parse for ViewType {
             braced!(inner in input);
             let body = inner.parse()?;
             Ok(ViewType::Memo { kw, expr, body })
+        } else if input.peek(kw::provide) {
+            let kw = input.parse::<kw::provide>()?;
+            let expr = Expr::parse_without_eager_brace(input)?;
+            let inner;
+            braced!(inner in input);
+            let body = inner.parse()?;
+            Ok(ViewType::Provide { kw, expr, body })
+        } else if input.peek(kw::task) && input.peek2(token::Brace) {
+            let kw = input.parse::<kw::task>()?;
+            let block = input.parse()?;
+            Ok(ViewType::Task { kw, block })
         } else {
             let name = input.parse()?;
 
@@ -260,13 +487,24 @@ impl Parse for ViewType {
                     children,
                 })
             } else {
-                let modifiers = if input.peek(token::Bracket) {
+                let mut modifiers = if input.peek(token::Bracket) {
                     let inner;
                     bracketed!(inner in input);
-                    Some(Punctuated::parse_terminated(&inner)?)
+                    Some(Punctuated::<ElemModifier, Token![,]>::parse_terminated(
+                        &inner,
+                    )?)
                 } else {
                     None
                 };
+                for m in modifiers.iter_mut().flatten() {
+                    match m {
+                        ElemModifier::Prop(ident, _, _, setter)
+                        | ElemModifier::PropOpt(ident, _, None, setter) => {
+                            *setter = prop_setter(&name, ident)?;
+                        }
+                        _ => {}
+                    }
+                }
                 let children = if input.peek(token::Brace) {
                     let inner;
                     braced!(inner in input);
@@ -323,7 +561,7 @@ impl ViewType {
                 }
                 for m in modifiers.iter().flatten() {
                     match m {
-                        ElemModifier::Attr(ident, expr, build) => {
+                        ElemModifier::Prop(ident, expr, build, None) => {
                             let build = build.map(|v| Ident::new("try", v.span));
                             if let Some(build) = build {
                                 out.extend(
@@ -333,16 +571,89 @@ impl ViewType {
                                 out.extend(quote! { .attr(stringify!(#ident), #expr) });
                             }
                         }
+                        ElemModifier::Prop(ident, expr, build, Some(setter)) => {
+                            let (method, build) = match build {
+                                Some(build) => {
+                                    let build = Ident::new("try", build.span);
+                                    (quote! { prop_build }, quote! { stringify!(#build); })
+                                }
+                                None => (quote! { prop }, quote! {}),
+                            };
+                            out.extend(quote! {
+                                .#method(stringify!(#ident), #expr, |node, value| {
+                                    #build
+                                    use ::lunar::__macro::*;
+                                    node.#setter((&&&PropArg(value)).prop_arg())
+                                })
+                            });
+                        }
+                        ElemModifier::Attr(ident, expr, build, attr) => {
+                            let attr = Ident::new("try", attr.span);
+                            let build = build.map(|v| Ident::new("try", v.span));
+                            if let Some(build) = build {
+                                out.extend(
+                                    quote! { .attr_build({ stringify!(#build); stringify!(#attr); stringify!(#ident) }, #expr) },
+                                );
+                            } else {
+                                out.extend(
+                                    quote! { .attr({ stringify!(#attr); stringify!(#ident) }, #expr) },
+                                );
+                            }
+                        }
+                        ElemModifier::AttrBind(ident, expr) => {
+                            out.extend(quote! { .attr_bind(stringify!(#ident), #expr) });
+                        }
+                        ElemModifier::Bind(name, state, signal) => {
+                            out.extend(quote! {
+                                .bind(stringify!(#name), stringify!(#signal), #state)
+                            });
+                        }
                         ElemModifier::OnSignal(name, expr) => {
                             out.extend(quote! { .on_signal(stringify!(#name), #expr) });
                         }
-                        ElemModifier::ThemeOverride { typ, name, value } => {
+                        ElemModifier::OnTyped(name, args, body) => {
+                            let forward = (0..args.len())
+                                .map(|i| Ident::new(&format!("arg{i}"), name.span()))
+                                .collect::<Vec<_>>();
+                            let args = args.iter();
+                            // so a wrong arity is reported on the signal name
+                            let connect = quote_spanned! {name.span()=>
+                                |node, cb| node.signals().#name().connect(move |#(#forward),*| cb.call((#(#forward,)*)))
+                            };
+                            out.extend(quote! {
+                                .on_typed(#connect, move |(#(#args,)*)| #body)
+                            });
+                        }
+                        ElemModifier::PropOpt(ident, expr, attr, None) => {
+                            let attr = attr.iter().map(|attr| Ident::new("try", attr.span));
+                            out.extend(quote! {
+                                .attr_opt({ #(stringify!(#attr);)* stringify!(#ident) }, #expr)
+                            });
+                        }
+                        ElemModifier::PropOpt(ident, expr, _, Some(setter)) => {
+                            out.extend(quote! {
+                                .prop_opt(stringify!(#ident), #expr, |node, value| {
+                                    use ::lunar::__macro::*;
+                                    node.#setter((&&&PropArg(value)).prop_arg())
+                                })
+                            });
+                        }
+                        ElemModifier::ThemeOverride {
+                            typ,
+                            name,
+                            opt,
+                            value,
+                        } => {
                             let typ = Ident::new(
                                 &format!("ThemeOverride{}", typ.to_string().to_upper_camel_case()),
                                 typ.span(),
                             );
+                            let method = match opt {
+                                Some(_) => quote! { theme_override_opt },
+                                None => quote! { theme_override },
+                            };
                             out.extend(
-                                quote! { .theme_override::<::lunar::#typ, _>(stringify!(#name), #value) },
+                                quote! { .#method::<::lunar::#typ, _>(stringify!(#name), #value) },
                             );
                         }
                         ElemModifier::NodeRef(expr) => {
@@ -389,6 +700,8 @@ impl ViewType {
             ViewType::State {
                 kw,
                 quiet,
+                eq,
+                kind,
                 name,
                 typ,
                 init,
@@ -396,21 +709,78 @@ impl ViewType {
             } => {
                 let body = body.gen_rust();
                 let kw = Ident::new("try", kw.span);
-                let quiet = quiet.map(|v| Ident::new("try", v.span));
-                if let Some(quiet) = quiet {
+                let mut out = if let StateKind::Persisted(persisted, args) = kind {
+                    let persisted = Ident::new("try", persisted.span);
                     quote! {
-                        {
-                            stringify!(#kw);
-                            stringify!(#quiet);
-                            ::lunar::stateful_quiet::<#typ, _, _, _>(move || #init, move |#name| #body)
-                        }
+                        stringify!(#kw);
+                        stringify!(#persisted);
+                        ::lunar::stateful_persisted::<#typ, _, _, _>(#args, move || #init, move |#name| #body)
+                    }
+                } else if let StateKind::Reducer(reducer, reduce) = kind {
+                    let reducer = Ident::new("try", reducer.span);
+                    quote! {
+                        stringify!(#kw);
+                        stringify!(#reducer);
+                        ::lunar::stateful_reducer::<#typ, _, _, _, _, _>(move || #init, #reduce, move |#name| #body)
+                    }
+                } else if let StateKind::History(history) = kind {
+                    let history = Ident::new("try", history.span);
+                    quote! {
+                        stringify!(#kw);
+                        stringify!(#history);
+                        ::lunar::stateful_history::<#typ, _, _, _>(move || #init, move |#name| #body)
+                    }
+                } else if let Some(quiet) = quiet {
+                    let quiet = Ident::new("try", quiet.span);
+                    quote! {
+                        stringify!(#kw);
+                        stringify!(#quiet);
+                        ::lunar::stateful_quiet::<#typ, _, _, _>(move || #init, move |#name| #body)
                     }
                 } else {
                     quote! {
-                        {
-                            stringify!(#kw);
-                            ::lunar::stateful::<#typ, _, _, _>(move || #init, move |#name| #body)
-                        }
+                        stringify!(#kw);
+                        ::lunar::stateful::<#typ, _, _, _>(move || #init, move |#name| #body)
+                    }
+                };
+                if let Some(eq) = eq {
+                    let eq = Ident::new("try", eq.span);
+                    out = quote! {
+                        stringify!(#eq);
+                        { #out }.skip_equal()
+                    };
+                }
+                quote! { { #out } }
+            }
+            ViewType::Computed {
+                kw,
+                name,
+                typ,
+                init,
+                body,
+            } => {
+                let body = body.gen_rust();
+                let kw = Ident::new("try", kw.span);
+                quote! {
+                    {
+                        stringify!(#kw);
+                        ::lunar::computed::<#typ, _, _, _>(move || #init, move |#name| #body)
+                    }
+                }
+            }
+            ViewType::Store {
+                kw,
+                name,
+                typ,
+                init,
+                body,
+            } => {
+                let body = body.gen_rust();
+                let kw = Ident::new("try", kw.span);
+                quote! {
+                    {
+                        stringify!(#kw);
+                        ::lunar::use_store::<#typ, _, _, _>(move || #init, move |#name| #body)
                     }
                 }
             }
@@ -434,6 +804,25 @@ impl ViewType {
                     }
                 }
             }
+            ViewType::Provide { kw, expr, body } => {
+                let body = body.gen_rust();
+                let kw = Ident::new("yield", kw.span);
+                quote! {
+                    {
+                        stringify!(#kw);
+                        ::lunar::provide(#expr, move || #body)
+                    }
+                }
+            }
+            ViewType::Task { kw, block } => {
+                let kw = Ident::new("async", kw.span);
+                quote! {
+                    {
+                        stringify!(#kw);
+                        ::lunar::task(async move #block)
+                    }
+                }
+            }
             ViewType::Let {
                 pat,
                 typ,